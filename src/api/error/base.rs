@@ -18,14 +18,26 @@ pub enum Kind {
     InternalFailure,
     /// ## Indicates 3rd party failure
     /// - e.g. schedule is formatted incorrectly
-    DataFailure
+    DataFailure,
+    /// ## Indicates missing or invalid credentials
+    /// - e.g. wrong token
+    AuthFailure,
+    /// ## Indicates that the request clashes with the server state
+    /// - e.g. updating a schedule that is ignored
+    Conflict,
+    /// ## Indicates that there is nothing to serve
+    /// - e.g. the endpoint is disabled
+    NotFound
 }
 impl Kind {
     pub fn status(&self) -> StatusCode {
         match self {
             Kind::UserFailure => StatusCode::BAD_REQUEST,
             Kind::InternalFailure => StatusCode::INTERNAL_SERVER_ERROR,
-            Kind::DataFailure => StatusCode::NOT_IMPLEMENTED
+            Kind::DataFailure => StatusCode::NOT_IMPLEMENTED,
+            Kind::AuthFailure => StatusCode::UNAUTHORIZED,
+            Kind::Conflict => StatusCode::CONFLICT,
+            Kind::NotFound => StatusCode::NOT_FOUND
         }
     }
 }
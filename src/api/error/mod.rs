@@ -69,7 +69,13 @@ macro_rules! api_err {
 
 #[derive(ToPrimitive, Serialize, Clone, Debug)]
 pub enum ErrorNum {
-    NoLastSchedule = 100
+    NoLastSchedule = 100,
+    Unauthorized = 101,
+    NoToken = 102,
+    UnknownSchedule = 103,
+    NoNowTarget = 104,
+    UnknownFormation = 105,
+    IgnoredSchedule = 106
 }
 impl ErrorNum {
     pub fn to_u32(&self) -> u32 {
@@ -86,3 +92,62 @@ api_err!(
         "no schedule found, make sure tables are still available and are valid"
     )
 );
+
+api_err!(
+    name:    Unauthorized,
+    as_enum: ErrorNum::Unauthorized,
+    kind:    Kind::AuthFailure,
+    error:   |_this| "missing or invalid bearer token".to_string()
+);
+
+api_err!(
+    name:    NoToken,
+    as_enum: ErrorNum::NoToken,
+    kind:    Kind::NotFound,
+    error:   |_this| "this endpoint is disabled, set server.token in settings.json to enable it".to_string()
+);
+
+api_err!(
+    name:    UnknownSchedule,
+    as_enum: ErrorNum::UnknownSchedule,
+    kind:    Kind::UserFailure,
+    fields:  (pub name: String),
+    error:   |this| format!(
+        "no schedule named {:?} in index.json",
+        this.name
+    )
+);
+
+api_err!(
+    name:    IgnoredSchedule,
+    as_enum: ErrorNum::IgnoredSchedule,
+    kind:    Kind::Conflict,
+    fields:  (pub name: String),
+    error:   |this| format!(
+        "schedule {:?} is in ignored of index.json and is never updated",
+        this.name
+    )
+);
+
+
+api_err!(
+    name:    NoNowTarget,
+    as_enum: ErrorNum::NoNowTarget,
+    kind:    Kind::UserFailure,
+    error:   |_this| "specify exactly one of group, teacher or cabinet".to_string()
+);
+
+api_err!(
+    name:    UnknownFormation,
+    as_enum: ErrorNum::UnknownFormation,
+    kind:    Kind::UserFailure,
+    fields:  (pub kind: schedule::raw::Kind, pub name: String),
+    error:   |this| format!(
+        "no {} named {:?} in the last schedule",
+        match this.kind {
+            schedule::raw::Kind::Groups => "group",
+            schedule::raw::Kind::Teachers => "teacher"
+        },
+        this.name
+    )
+);
\ No newline at end of file
@@ -4,24 +4,86 @@ pub mod schedule;
 use chrono::NaiveDateTime;
 use actix_web::{http::StatusCode, HttpResponse, HttpResponseBuilder};
 use serde_derive::Serialize;
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use crate::data::schedule as sc;
+use crate::{compare, data::schedule as sc};
 use error::base::ApiError;
 
 
+/// # Names of changed formations of one kind
+#[derive(Serialize)]
+pub struct KindSummary {
+    pub appeared: Vec<String>,
+    pub disappeared: Vec<String>,
+    pub changed: Vec<String>
+}
+impl KindSummary {
+    pub fn from_changes(changes: &compare::schedule::Page) -> Self {
+        let formations = &changes.formations;
+
+        Self {
+            appeared: formations.appeared.iter()
+                .map(|form| form.name.clone())
+                .collect(),
+            disappeared: formations.disappeared.iter()
+                .map(|form| form.name.clone())
+                .collect(),
+            changed: formations.changed.iter()
+                .filter_map(|form| form.name.clone())
+                .collect()
+        }
+    }
+}
+
+/// # What an update changed
+#[derive(Serialize)]
+pub struct Summary {
+    pub groups: Option<KindSummary>,
+    pub teachers: Option<KindSummary>
+}
+impl Summary {
+    pub fn from_notify(notify: &sc::Notify) -> Self {
+        Self {
+            groups: notify.groups.as_ref().map(KindSummary::from_changes),
+            teachers: notify.teachers.as_ref().map(KindSummary::from_changes)
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            groups: None,
+            teachers: None
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct Updates {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub period: Option<std::time::Duration>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub last: Option<NaiveDateTime>
+    pub last: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<BTreeMap<String, sc::raw::Health>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<Vec<sc::report::SourceReport>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub near_matches: Option<Vec<sc::report::NearMatch>>
 }
 impl Default for Updates {
     fn default() -> Self {
         Self {
             period: None,
-            last: None
+            last: None,
+            next: None,
+            health: None,
+            summary: None,
+            report: None,
+            near_matches: None
         }
     }
 }
@@ -33,12 +95,48 @@ impl Updates {
         }
     }
 
+    pub fn from_period_next(period: std::time::Duration, next: NaiveDateTime) -> Self {
+        Self {
+            period: Some(period),
+            next: Some(next),
+            ..Default::default()
+        }
+    }
+
     pub fn from_last(last: NaiveDateTime) -> Self {
         Self {
             last: Some(last),
             ..Default::default()
         }
     }
+
+    pub fn from_health(health: BTreeMap<String, sc::raw::Health>) -> Self {
+        Self {
+            health: Some(health),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_report(
+        last: Option<NaiveDateTime>,
+        report: Vec<sc::report::SourceReport>,
+        near_matches: Vec<sc::report::NearMatch>
+    ) -> Self {
+        Self {
+            last,
+            report: Some(report),
+            near_matches: Some(near_matches),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_trigger(last: NaiveDateTime, summary: Summary) -> Self {
+        Self {
+            last: Some(last),
+            summary: Some(summary),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -46,13 +144,16 @@ pub struct Data {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<Arc<sc::Page>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub updates: Option<Updates>
+    pub updates: Option<Updates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub now: Option<sc::Now>
 }
 impl Default for Data {
     fn default() -> Self {
         Self {
             page: None,
-            updates: None
+            updates: None,
+            now: None
         }
     }
 }
@@ -71,6 +172,13 @@ impl Data {
         }
     }
 
+    pub fn from_now(now: sc::Now) -> Self {
+        Self {
+            now: Some(now),
+            ..Default::default()
+        }
+    }
+
     pub fn from_updates_period(period: std::time::Duration) -> Self {
         Self::from_updates(Updates::from_period(period))
     }
@@ -78,6 +186,10 @@ impl Data {
     pub fn from_updates_last(last: NaiveDateTime) -> Self {
         Self::from_updates(Updates::from_last(last))
     }
+
+    pub fn from_updates_health(health: BTreeMap<String, sc::raw::Health>) -> Self {
+        Self::from_updates(Updates::from_health(health))
+    }
 }
 
 #[derive(Serialize)]
@@ -116,6 +228,13 @@ impl Response {
         }
     }
 
+    pub fn from_now(now: sc::Now) -> Self {
+        Self {
+            data: Some(Data::from_now(now)),
+            ..Default::default()
+        }
+    }
+
     pub fn from_updates_period(period: std::time::Duration) -> Self {
         Self {
             data: Some(Data::from_updates(Updates::from_period(period))),
@@ -130,6 +249,13 @@ impl Response {
         }
     }
 
+    pub fn from_updates_health(health: BTreeMap<String, sc::raw::Health>) -> Self {
+        Self {
+            data: Some(Data::from_updates(Updates::from_health(health))),
+            ..Default::default()
+        }
+    }
+
     pub fn to_json(self) -> HttpResponse {
         let resp = HttpResponseBuilder::new(
             if self.error.is_none() {
@@ -1,10 +1,29 @@
 use actix_web::{get, Responder, web};
+use serde_derive::Deserialize;
 
 use crate::data::schedule::raw::Kind;
-use crate::api::schedule::{generic_get, ScheduleGetNameQuery};
+use crate::api::schedule::generic_get;
 
 
+#[derive(Deserialize)]
+struct GroupsGetQuery {
+    name: Option<String>,
+    course: Option<u32>,
+    speciality: Option<String>
+}
+
 #[get("/schedule/groups")]
-async fn get(query: web::Query<ScheduleGetNameQuery>) -> impl Responder {
-    generic_get(Kind::Groups, query).await
+async fn get(query: web::Query<GroupsGetQuery>) -> impl Responder {
+    let is_filtered = query.course.is_some() || query.speciality.is_some();
+
+    generic_get(Kind::Groups, query.name.as_deref(), |form| {
+        if !is_filtered {
+            return true
+        }
+
+        form.code.as_ref().is_some_and(|code| code.matches(
+            query.course,
+            query.speciality.as_deref()
+        ))
+    }).await
 }
@@ -1,15 +1,17 @@
 pub mod teachers;
 pub mod groups;
+pub mod now;
 
 use serde_derive::Deserialize;
 use actix::{Actor, StreamHandler, SpawnHandle, AsyncContext};
-use actix_web::{web::{self, Bytes}, Responder, get, HttpRequest, HttpResponse};
+use actix_web::{web::{self, Bytes}, http::header, Responder, get, post, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
 use log::debug;
-use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use std::{collections::BTreeMap, sync::Arc};
 
-use crate::{options, data::schedule::raw::Kind};
-use super::{error::{self, base::ToApiError}, ToResponse, Response};
+use crate::{options, data::schedule::{raw::{index::UpdateFinishType, Kind}, Formation}};
+use super::{error::{self, base::ToApiError}, ToResponse, Response, Summary, Updates};
 
 
 #[derive(Deserialize)]
@@ -17,9 +19,12 @@ struct ScheduleGetNameQuery {
     name: Option<String>
 }
 
+/// # Last page of `kind`, only with
+/// formations named `name` and passing `filter`
 async fn generic_get(
     kind: Kind,
-    query: web::Query<ScheduleGetNameQuery>
+    name: Option<&str>,
+    filter: impl Fn(&Formation) -> bool
 ) -> HttpResponse {
     let page_guard = match kind {
         Kind::Groups => options().schedule.last.groups.read().await,
@@ -33,12 +38,18 @@ async fn generic_get(
             .to_json()
     };
 
-    if let Some(name) = query.name.as_ref() {
+    if let Some(name) = name {
         let mut cloned_page = (*page).clone();
         cloned_page.remove_except(name);
         page = Arc::new(cloned_page);
     }
 
+    if !page.formations.iter().all(&filter) {
+        let mut cloned_page = (*page).clone();
+        cloned_page.formations.retain(|form| filter(form));
+        page = Arc::new(cloned_page);
+    }
+
     Response::from_page(
         page.clone()
     ).to_json()
@@ -112,14 +123,116 @@ async fn updates(
 
 #[get("/schedule/updates/period")]
 async fn updates_period() -> impl Responder {
-    Response::from_updates_period(
-        options().schedule.index.period.to_std().unwrap()
-    ).to_json()
+    let index = options().schedule.index();
+
+    Response::from_updates(Updates::from_period_next(
+        index.update_period().to_std().unwrap(),
+        index.next_update().await
+    )).to_json()
 }
 
 #[get("/schedule/updates/last")]
 async fn updates_last() -> impl Responder {
     Response::from_updates_last(
-        *options().schedule.index.updated.read().await
+        *options().schedule.index().updated.read().await
     ).to_json()
+}
+
+#[get("/schedule/updates/health")]
+async fn updates_health() -> impl Responder {
+    let mut health = BTreeMap::new();
+
+    for schedule in options().schedule.index().types.iter() {
+        health.insert(
+            schedule.name.clone(),
+            schedule.health.read().await.clone()
+        );
+    }
+
+    Response::from_updates_health(health).to_json()
+}
+
+#[get("/schedule/updates/report")]
+async fn updates_report() -> impl Responder {
+    let report = &options().schedule.report;
+
+    Response::from_updates(Updates::from_report(
+        *report.updated.read().await,
+        report.sources.read().await.clone(),
+        report.near_matches.read().await.clone()
+    )).to_json()
+}
+
+fn is_authorized(req: &HttpRequest, token: &str) -> bool {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| given.as_bytes().ct_eq(token.as_bytes()).into())
+}
+
+#[post("/schedule/updates/trigger")]
+async fn updates_trigger(
+    req: HttpRequest,
+    query: web::Query<ScheduleGetNameQuery>
+) -> HttpResponse {
+    let Some(token) = options().settings().server.token.clone() else {
+        return error::NoToken::new()
+            .to_api_error()
+            .to_response()
+            .to_json()
+    };
+
+    if !is_authorized(&req, &token) {
+        return error::Unauthorized::new()
+            .to_api_error()
+            .to_response()
+            .to_json()
+    }
+
+    if let Some(name) = query.name.as_ref() {
+        let index = options().schedule.index();
+
+        if index.schedule_by_name(name).is_none() {
+            return error::UnknownSchedule::new(name.clone())
+                .to_api_error()
+                .to_response()
+                .to_json()
+        }
+
+        if index.ignored.contains(name) {
+            return error::IgnoredSchedule::new(name.clone())
+                .to_api_error()
+                .to_response()
+                .to_json()
+        }
+    }
+
+    let notify_rx = options().schedule.clone().get_notify_rx();
+    let only = query.name.clone().map(|name| vec![name]);
+
+    debug!("update triggered for {:?}", query.name);
+    let random_before = loop {
+        let random_before = notify_rx.borrow().random.clone();
+        let finish = options().schedule.index().update_all(only.clone()).await;
+
+        // the running update may not have included
+        // the schedule asked for, then it goes next
+        match finish {
+            UpdateFinishType::LockRelease { is_covered: false } if only.is_some() => continue,
+            _ => break random_before
+        }
+    };
+
+    let notify = notify_rx.borrow().clone();
+    let summary = if notify.random != random_before {
+        Summary::from_notify(&notify)
+    } else {
+        Summary::empty()
+    };
+
+    Response::from_updates(Updates::from_trigger(
+        *options().schedule.index().updated.read().await,
+        summary
+    )).to_json()
 }
\ No newline at end of file
@@ -0,0 +1,58 @@
+use actix_web::{get, HttpResponse, web};
+use serde_derive::Deserialize;
+
+use crate::{
+    options,
+    api::{error::{self, base::ToApiError}, ToResponse, Response},
+    data::schedule::{now::Target, raw::Kind, Now},
+    parse
+};
+
+
+#[derive(Deserialize)]
+struct NowQuery {
+    group: Option<String>,
+    teacher: Option<String>,
+    cabinet: Option<String>
+}
+
+#[get("/schedule/now")]
+async fn get(query: web::Query<NowQuery>) -> HttpResponse {
+    let room = query.cabinet.as_deref().map(parse::cabinet::room);
+
+    let (kind, target) = match (&query.group, &query.teacher, &room) {
+        (Some(group), None, None) => (Kind::Groups, Target::Formation(group)),
+        (None, Some(teacher), None) => (Kind::Teachers, Target::Formation(teacher)),
+        // both pages are complemented, groups have every cabinet
+        (None, None, Some(room)) => (Kind::Groups, Target::Cabinet(room)),
+        _ => return error::NoNowTarget::new()
+            .to_api_error()
+            .to_response()
+            .to_json()
+    };
+
+    let page_guard = match kind {
+        Kind::Groups => options().schedule.last.groups.read().await,
+        Kind::Teachers => options().schedule.last.teachers.read().await
+    };
+
+    let Some(page) = page_guard.clone() else {
+        return error::NoLastSchedule::new(kind)
+            .to_api_error()
+            .to_response()
+            .to_json()
+    };
+
+    if let Target::Formation(name) = target {
+        if !page.formations.iter().any(|formation| formation.name == name) {
+            return error::UnknownFormation::new(kind, name.to_string())
+                .to_api_error()
+                .to_response()
+                .to_json()
+        }
+    }
+
+    let now = Now::find(&page, target, options().settings().now());
+
+    Response::from_now(now).to_json()
+}
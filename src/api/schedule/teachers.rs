@@ -6,5 +6,5 @@ use crate::api::schedule::{generic_get, ScheduleGetNameQuery};
 
 #[get("/schedule/teachers")]
 async fn get(query: web::Query<ScheduleGetNameQuery>) -> impl Responder {
-    generic_get(Kind::Teachers, query).await
+    generic_get(Kind::Teachers, query.name.as_deref(), |_form| true).await
 }
@@ -75,9 +75,13 @@ impl DetailedCmp<regular::Attender, Attender> for Attender {
 pub struct Subject {
     pub raw: Option<String>,
     pub name: Option<String>,
+    /// # Subjects are only matched within the same subgroup
+    pub subgroup: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num: Option<Primitive<u32>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Primitive<regular::Status>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub attenders: Option<DetailedChanges<regular::Attender, Attender>>,
 }
 impl DetailedCmp<regular::Subject, Subject> for Subject {
@@ -99,10 +103,21 @@ impl DetailedCmp<regular::Subject, Subject> for Subject {
         } else {
             None
         };
+        let subgroup = if let Some(new) = &new {
+            new.subgroup
+        } else if let Some(old) = &old {
+            old.subgroup
+        } else {
+            None
+        };
         let num = Primitive::new(
             old.as_ref().map(|old| old.num),
             new.as_ref().map(|new| new.num),
         );
+        let status = Primitive::new(
+            old.as_ref().map(|old| old.status),
+            new.as_ref().map(|new| new.status),
+        );
         let attenders = DetailedChanges::compare(
             old.as_ref().map(|old| old.attenders.clone()),
             new.as_ref().map(|new| new.attenders.clone()),
@@ -111,11 +126,17 @@ impl DetailedCmp<regular::Subject, Subject> for Subject {
         Self {
             raw,
             name,
+            subgroup,
             num: if num.is_different_hash() {
                 Some(num)
             } else {
                 None
             },
+            status: if status.is_different_hash() {
+                Some(status)
+            } else {
+                None
+            },
             attenders: if attenders.has_changes() {
                 Some(attenders)
             } else {
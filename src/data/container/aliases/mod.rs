@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::BTreeMap, sync::Arc, path::PathBuf};
+use serde_derive::{Serialize, Deserialize};
+use crate::{SyncResult, data::json::{
+    self,
+    Saving,
+    DirectLoading
+}};
+
+
+#[derive(thiserror::Error, Debug)]
+#[error("aliases error")]
+pub enum AliasesError {
+    /// # Canonical name is an alias itself
+    Chain(String),
+    /// # Name marked as distinct is an alias
+    DistinctAlias(String)
+}
+
+
+/// # Alternative spellings of names
+/// Only teachers for now, keys are either
+/// the raw spelling from a table or
+/// the name after validation.
+/// 
+/// Close names that are different people
+/// are kept in `distinct_teachers`
+#[derive(Debug)]
+pub struct Aliases {
+    path: PathBuf,
+    pub teachers: BTreeMap<String, String>,
+    pub distinct_teachers: Vec<[String; 2]>
+}
+impl Aliases {
+    fn default(path: PathBuf) -> Arc<Self> {
+        let this = Self {
+            path,
+            teachers: BTreeMap::new(),
+            distinct_teachers: vec![]
+        };
+
+        Arc::new(this)
+    }
+
+    pub fn validate(&self) -> Result<(), AliasesError> {
+        for canonical in self.teachers.values() {
+            if self.teachers.contains_key(canonical) {
+                return Err(AliasesError::Chain(canonical.clone()))
+            }
+        }
+
+        for name in self.distinct_teachers.iter().flatten() {
+            if self.teachers.contains_key(name) {
+                return Err(AliasesError::DistinctAlias(name.clone()))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Canonical teacher name of `raw` or `name`
+    pub fn teacher(&self, raw: &str, name: &str) -> Option<&str> {
+        self.teachers
+            .get(raw.trim())
+            .or_else(|| self.teachers.get(name))
+            .map(|canonical| canonical.as_str())
+    }
+
+    /// # If `a` and `b` are marked as different teachers
+    pub fn are_distinct_teachers(&self, a: &str, b: &str) -> bool {
+        self.distinct_teachers
+            .iter()
+            .any(|[first, second]| {
+                (first == a && second == b) ||
+                (first == b && second == a)
+            })
+    }
+
+    /// # Load, returning an error if some alias is invalid
+    pub async fn try_load(path: PathBuf) -> SyncResult<Arc<Self>> {
+        let middle = MiddleAliases::load(path.clone()).await?;
+        let this = Self {
+            path,
+            teachers: middle.teachers.clone(),
+            distinct_teachers: middle.distinct_teachers.clone()
+        };
+        this.validate()?;
+
+        Ok(Arc::new(this))
+    }
+
+    pub async fn load_or_init(path: PathBuf) -> SyncResult<Arc<Self>> {
+        let this;
+
+        if path.exists() {
+            this = Self::try_load(path).await?;
+        } else {
+            this = Self::default(path);
+            this.clone().save().await?;
+        }
+
+        Ok(this)
+    }
+}
+impl json::ToMiddle<MiddleAliases> for Aliases {
+    async fn to_middle(&self) -> MiddleAliases {
+        MiddleAliases {
+            path: self.path.clone(),
+            teachers: self.teachers.clone(),
+            distinct_teachers: self.distinct_teachers.clone()
+        }
+    }
+}
+impl json::Saving<MiddleAliases> for Aliases {}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct MiddleAliases {
+    #[serde(skip)]
+    path: PathBuf,
+    /// # Spelling to canonical teacher name
+    #[serde(default)]
+    pub teachers: BTreeMap<String, String>,
+    /// # Pairs of close names that are different teachers
+    #[serde(default)]
+    pub distinct_teachers: Vec<[String; 2]>
+}
+impl json::Path for MiddleAliases {
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+}
+impl json::DirectSaving for MiddleAliases {}
+impl json::DirectLoading for MiddleAliases {}
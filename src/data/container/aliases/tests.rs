@@ -0,0 +1,54 @@
+use super::*;
+
+
+fn aliases(teachers: &[(&str, &str)]) -> Aliases {
+    Aliases {
+        path: PathBuf::new(),
+        teachers: teachers
+            .iter()
+            .map(|(alias, canonical)| (alias.to_string(), canonical.to_string()))
+            .collect(),
+        distinct_teachers: vec![]
+    }
+}
+
+#[test]
+fn test_teacher() {
+    let aliases = aliases(&[
+        ("Иванва А.А.", "Иванова А.А."),
+        ("Иванова А.", "Иванова А.А."),
+    ]);
+
+    assert_eq!(aliases.teacher("Иванва А. А.", "Иванва А.А."), Some("Иванова А.А."));
+    assert_eq!(aliases.teacher(" Иванова А. ", "Иванова А."), Some("Иванова А.А."));
+    assert_eq!(aliases.teacher("Иванова А.А.", "Иванова А.А."), None);
+}
+
+#[test]
+fn test_validate() {
+    assert!(aliases(&[("Иванва А.А.", "Иванова А.А.")]).validate().is_ok());
+    assert!(matches!(
+        aliases(&[
+            ("Иванва А.А.", "Иванова А.А."),
+            ("Иванова А.А.", "Иванова А.Б."),
+        ]).validate(),
+        Err(AliasesError::Chain(name)) if name == "Иванова А.А."
+    ));
+
+    let mut distinct = aliases(&[("Иванва А.А.", "Иванова А.А.")]);
+    distinct.distinct_teachers.push(["Иванов А.А.".to_string(), "Иванва А.А.".to_string()]);
+    assert!(matches!(
+        distinct.validate(),
+        Err(AliasesError::DistinctAlias(name)) if name == "Иванва А.А."
+    ));
+}
+
+#[test]
+fn test_distinct_teachers() {
+    let mut aliases = aliases(&[]);
+    aliases.distinct_teachers.push(["Иванов А.А.".to_string(), "Иванова А.А.".to_string()]);
+
+    assert!(aliases.are_distinct_teachers("Иванов А.А.", "Иванова А.А."));
+    assert!(aliases.are_distinct_teachers("Иванова А.А.", "Иванов А.А."));
+    assert!(!aliases.are_distinct_teachers("Иванов А.А.", "Иванов А.Б."));
+}
@@ -1,11 +1,17 @@
 mod settings;
 mod schedule;
+mod aliases;
 
-use std::{path::PathBuf, sync::Arc};
+#[cfg(test)]
+mod tests;
+
+use log::{info, warn};
+use std::{path::PathBuf, sync::{Arc, RwLock}};
 use crate::{
     data::container::{
         settings::Settings,
-        schedule::Schedule
+        schedule::Schedule,
+        aliases::Aliases
     },
     SyncResult
 };
@@ -15,7 +21,10 @@ use crate::{
 pub struct Container {
     pub dir: PathBuf,
 
-    pub settings: Arc<Settings>,
+    /// # Swapped on reload
+    settings: RwLock<Arc<Settings>>,
+    /// # Swapped on reload
+    aliases: RwLock<Arc<Aliases>>,
     pub schedule: Arc<Schedule>,
 }
 impl Container {
@@ -26,9 +35,12 @@ impl Container {
 
         let this = Container {
             dir: dir.clone(),
-            settings: Settings::load_or_init(
+            settings: RwLock::new(Settings::load_or_init(
                 dir.join("settings.json")
-            ).await?,
+            ).await?),
+            aliases: RwLock::new(Aliases::load_or_init(
+                dir.join("aliases.json")
+            ).await?),
             schedule: Schedule::default_from_dir(
                 dir.join("schedule")
             ).await?
@@ -36,4 +48,37 @@ impl Container {
 
         Ok(this)
     }
+
+    pub fn settings(&self) -> Arc<Settings> {
+        self.settings.read().unwrap().clone()
+    }
+
+    pub fn aliases(&self) -> Arc<Aliases> {
+        self.aliases.read().unwrap().clone()
+    }
+
+    /// # Read `settings.json`, `aliases.json` and `index.json` again
+    /// All are validated before anything is swapped,
+    /// if one is invalid, the old configuration keeps running.
+    /// New aliases apply from the next update.
+    pub async fn reload(&self) -> SyncResult<()> {
+        let settings = Settings::try_load(self.dir.join("settings.json")).await?;
+        let aliases = Aliases::try_load(self.dir.join("aliases.json")).await?;
+
+        self.schedule.reload_index().await?;
+
+        if settings.server.address != self.settings().server.address {
+            warn!("server.address changed, restart to apply it");
+        }
+        if settings.regex != self.settings().regex {
+            warn!("regex changed, restart to apply it");
+        }
+
+        *self.settings.write().unwrap() = settings;
+        *self.aliases.write().unwrap() = aliases;
+
+        info!("configuration reloaded");
+
+        Ok(())
+    }
 }
\ No newline at end of file
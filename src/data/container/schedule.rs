@@ -1,16 +1,22 @@
 use log::{info, warn, debug};
 use tokio::sync::{RwLock, mpsc, watch};
-use std::{path::PathBuf, sync::Arc};
+use std::{collections::{BTreeMap, HashMap, HashSet}, path::PathBuf, sync::Arc};
 use crate::{
     compare::{self, DetailedCmp},
     data::{
         json::Saving,
-        schedule::{raw, Last, Notify, Page}
+        schedule::{raw, report, Last, Notify, Page, Report}
     },
     merge, parse, string, SyncResult
 };
 
 
+const REPLAY_DIR: &str = "replay";
+
+/// # Parsed pages of a schedule type by file path
+type FilePages = BTreeMap<PathBuf, Vec<Page>>;
+
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct Schedule {
@@ -21,8 +27,24 @@ pub struct Schedule {
     notify_tx: watch::Sender<Arc<Notify>>,
     notify_rx: watch::Receiver<Arc<Notify>>,
 
+    /// # Last parsed pages of each schedule type (by name)
+    /// and each of its files (by path)
+    /// Reused for sources that didn't change
+    /// since the previous update, and for files
+    /// that failed to parse this time.
+    pages: Arc<RwLock<HashMap<String, FilePages>>>,
+    /// # Schedule types whose `pages` are marked stale
+    /// because their update gave up
+    /// The cache lives in memory only, after a restart
+    /// a failing source is parsed from its newest archived
+    /// download, or has no pages at all if there is none.
+    failed: Arc<RwLock<HashSet<String>>>,
+
+    /// # Swapped on reload
+    index: std::sync::RwLock<Arc<raw::Index>>,
+
     pub last: Arc<Last>,
-    pub index: Arc<raw::Index>
+    pub report: Arc<Report>,
 }
 impl Schedule {
     pub async fn default_from_dir(dir: PathBuf) -> SyncResult<Arc<Schedule>> {
@@ -50,14 +72,20 @@ impl Schedule {
             notify_tx,
             notify_rx,
 
+            pages: Arc::new(RwLock::new(HashMap::new())),
+            failed: Arc::new(RwLock::new(HashSet::new())),
+
             last: Last::load_or_init(
                 dir.join("last.json")
             ).await?,
-            index: raw::Index::load_or_init(
+            report: Report::load_or_init(
+                dir.join("report.json")
+            ).await?,
+            index: std::sync::RwLock::new(raw::Index::load_or_init(
                 dir.join("index.json"),
                 updated_tx,
                 converted_rx,
-            ).await?
+            ).await?)
         };
 
         let this = Arc::new(this);
@@ -74,6 +102,101 @@ impl Schedule {
         self.notify_rx.clone()
     }
 
+    pub fn index(&self) -> Arc<raw::Index> {
+        self.index.read().unwrap().clone()
+    }
+
+    /// # Read `index.json` again and swap it in
+    pub async fn reload_index(&self) -> SyncResult<()> {
+        let new = self.index().reload().await?;
+        *self.index.write().unwrap() = new;
+
+        Ok(())
+    }
+
+    /// # Feed archived downloads through the pipeline again
+    /// Starts from an empty schedule and goes
+    /// in chronological order. Every diff is saved
+    /// as `replay/<timestamp>-<name>.json`.
+    pub async fn replay(self: Arc<Self>) -> SyncResult<()> {
+        let archived = self.index().archived().await;
+        info!("replaying {} archives", archived.len());
+
+        *self.last.groups.write().await = None;
+        *self.last.teachers.write().await = None;
+        self.pages.write().await.clear();
+        self.failed.write().await.clear();
+
+        let history_dir = self.dir.join(REPLAY_DIR);
+        tokio::fs::create_dir_all(&history_dir).await?;
+
+        let mut replayed: Vec<Arc<raw::index::Schedule>> = vec![];
+
+        for (time, schedule, path) in archived {
+            if !replayed.iter().any(|sc| sc.name == schedule.name) {
+                replayed.push(schedule.clone());
+            }
+
+            let random_before = self.notify_rx.borrow().random.clone();
+
+            let result = self.index().replay(&schedule, &path, &replayed).await;
+            if let Err(error) = result {
+                warn!("failed to replay {:?}: {:?}", path, error);
+                continue;
+            }
+
+            let notify = self.notify_rx.borrow().clone();
+            if notify.random == random_before {
+                continue;
+            }
+
+            let notify_path = history_dir.join(format!(
+                "{}-{}.json",
+                time.format(raw::archive::TIMESTAMP_FORMAT),
+                schedule.name
+            ));
+            tokio::fs::write(&notify_path, serde_json::to_vec_pretty(&*notify)?).await?;
+            info!("replayed {:?}, diff saved to {:?}", path, notify_path);
+        }
+
+        info!("replay finished");
+
+        Ok(())
+    }
+
+    /// # Save the report of this update
+    /// Sources that weren't parsed this time
+    /// keep their files from the previous report,
+    /// `near_matches` stay if there was no conversion.
+    async fn report_updates(
+        self: Arc<Self>,
+        holders: &[raw::index::PathHolder],
+        mut file_reports: HashMap<String, Vec<report::FileReport>>,
+        near_matches: Option<Vec<report::NearMatch>>
+    ) {
+        let mut sources = vec![];
+
+        for holder in holders {
+            let files = match file_reports.remove(&holder.name) {
+                Some(files) => files,
+                None => self.report
+                    .source(&holder.name).await
+                    .map(|source| source.files)
+                    .unwrap_or_default()
+            };
+
+            sources.push(report::SourceReport {
+                name: holder.name.clone(),
+                kind: holder.kind,
+                unchanged: holder.unchanged,
+                failed: holder.failed,
+                files
+            });
+        }
+
+        self.report.clone().set(sources, near_matches).await;
+    }
+
     pub async fn await_updates(self: Arc<Self>) {
         loop {
             let mut rx = self.updated_rx.write().await;
@@ -81,32 +204,142 @@ impl Schedule {
             debug!("updated signal received");
             std::mem::drop(rx);
 
-            let group_paths = paths.iter()
-                .filter(|holder| holder.kind == raw::Kind::Groups)
-                .map(|holder| holder.paths.as_slice())
+            for holder in paths.iter().filter(|holder| holder.failed) {
+                warn!("{} failed to update, using its previous content", holder.name);
+            }
+
+            let mut pages = self.pages.write().await;
+            let mut failed = self.failed.write().await;
+
+            // forget schedule types that are no longer updated
+            let names_before = pages.len();
+            pages.retain(|name, _| paths.iter().any(|holder| &holder.name == name));
+            failed.retain(|name| pages.contains_key(name));
+            let types_removed = pages.len() != names_before;
+
+            // pages of a failed update have to be marked stale,
+            // and stale ones parsed again once it succeeds
+            let is_reusable = |holder: &raw::index::PathHolder| {
+                holder.unchanged
+                && pages.contains_key(&holder.name)
+                && holder.failed == failed.contains(&holder.name)
+            };
+
+            let all_unchanged = paths.iter().all(is_reusable);
+            if paths.is_empty() || (all_unchanged && !types_removed) {
+                debug!("no schedule changed, skipping conversion");
+                std::mem::drop(failed);
+                std::mem::drop(pages);
+                self.clone().report_updates(&paths, HashMap::new(), None).await;
+                self.converted_tx.read().await.send(()).await.unwrap();
+                debug!("converted signal sent");
+                continue;
+            }
+
+            let mut handles = vec![];
+
+            for holder in paths.iter() {
+                let is_cached = holder.unchanged && pages.contains_key(&holder.name);
+
+                if is_cached && holder.failed {
+                    debug!("{} failed, reusing parsed pages as stale", holder.name);
+                    pages.get_mut(&holder.name)
+                        .into_iter()
+                        .flat_map(|files| files.values_mut())
+                        .flatten()
+                        .for_each(|page| page.mark_stale());
+                    failed.insert(holder.name.clone());
+                    continue;
+                }
+
+                if is_cached && !failed.contains(&holder.name) {
+                    debug!("{} is unchanged, reusing parsed pages", holder.name);
+                    continue;
+                }
+
+                let name = holder.name.clone();
+                let kind = holder.kind;
+                let is_failed = holder.failed;
+                let holder_files = holder.files.clone();
+                let limits = self.index().limits.clone();
+                let handle = tokio::spawn(async move {
+                    let results = match kind {
+                        raw::Kind::Groups => parse::groups(holder_files.as_slice(), &limits).await,
+                        raw::Kind::Teachers => parse::teachers(holder_files.as_slice(), &limits).await
+                    };
+                    (name, is_failed, results)
+                });
+                handles.push(handle);
+            }
+
+            let mut file_reports = HashMap::new();
+
+            for handle in handles {
+                let (name, is_failed, file_results) = handle.await.unwrap();
+                let mut previous_files = pages.remove(&name).unwrap_or_default();
+                let mut files = FilePages::new();
+                let mut reports = vec![];
+
+                for parsed in file_results {
+                    let path = parsed.path;
+                    let errors = parsed.results
+                        .iter()
+                        .filter_map(|result| result.as_ref().err())
+                        .map(|error| format!("{:?}", error))
+                        .collect::<Vec<String>>();
+                    let parsed_pages = parsed.results
+                        .into_iter()
+                        .filter_map(|result| result.ok())
+                        .collect::<Vec<Page>>();
+
+                    let (status, file_pages) = match previous_files.remove(&path) {
+                        Some(mut previous_pages) if !errors.is_empty() => {
+                            warn!(
+                                "{:?} of {} failed to parse, keeping its previous pages as stale",
+                                path,
+                                name
+                            );
+                            previous_pages.iter_mut().for_each(|page| page.mark_stale());
+                            (report::FileStatus::Stale, previous_pages)
+                        },
+                        _ if !errors.is_empty() => (report::FileStatus::Failed, parsed_pages),
+                        _ => (report::FileStatus::Ok, parsed_pages)
+                    };
+
+                    reports.push(report::FileReport::new(
+                        path.clone(),
+                        status,
+                        errors,
+                        &file_pages,
+                        parsed.took
+                    ));
+                    files.insert(path, file_pages);
+                }
+
+                if is_failed {
+                    files.values_mut().flatten().for_each(|page| page.mark_stale());
+                    failed.insert(name.clone());
+                } else {
+                    failed.remove(&name);
+                }
+
+                pages.insert(name.clone(), files);
+                file_reports.insert(name, reports);
+            }
+
+            let pages_of_kind = |kind: raw::Kind| paths.iter()
+                .filter(|holder| holder.kind == kind)
+                .filter_map(|holder| pages.get(&holder.name))
+                .flat_map(|files| files.values())
                 .flatten()
                 .cloned()
-                .collect::<Vec<PathBuf>>();
-            let teacher_paths = paths.iter()
-                .filter(|holder| holder.kind == raw::Kind::Teachers)
-                .map(|holder| holder.paths.as_slice())
-                .flat_map(|paths| paths)
-                .cloned()
-                .collect::<Vec<PathBuf>>();
+                .collect::<Vec<Page>>();
 
-            let groups_handle = tokio::spawn(async move {
-                parse::groups(group_paths.as_slice()).await
-            });
-            let teachers_handle = tokio::spawn(async move {
-                parse::teachers(teacher_paths.as_slice()).await
-            });
+            let group_pages = pages_of_kind(raw::Kind::Groups);
+            let teacher_pages = pages_of_kind(raw::Kind::Teachers);
+            std::mem::drop(failed);
+            std::mem::drop(pages);
 
-            let group_pages = groups_handle
-                .await
-                .unwrap()
-                .into_iter()
-                .filter_map(|result| result.ok())
-                .collect::<Vec<Page>>();
             let groups_min_date = group_pages
                 .iter()
                 .min_by(|a, b| a.date.start().cmp(b.date.start()))
@@ -123,12 +356,6 @@ impl Schedule {
                 None
             };
 
-            let teacher_pages = teachers_handle
-                .await
-                .unwrap()
-                .into_iter()
-                .filter_map(|result| result.ok())
-                .collect::<Vec<Page>>();
             let teachers_min_date = teacher_pages
                 .iter()
                 .min_by(|a, b| a.date.start().cmp(b.date.start()))
@@ -157,13 +384,25 @@ impl Schedule {
                 None
             };
 
+            let mut near_matches = vec![];
+
             if let (Some(groups), Some(teachers)) = (groups.as_mut(), teachers.as_mut()) {
-                let result = merge::complement(groups, teachers);
-                if let Err(err) = result {
-                    warn!("complementing groups with teachers error: {:?}", err);
+                match merge::complement(groups, teachers) {
+                    Ok(matches) => near_matches = matches,
+                    Err(err) => warn!("complementing groups with teachers error: {:?}", err)
                 }
             }
 
+            if !near_matches.is_empty() {
+                info!("{} teacher names are close to others, see the report", near_matches.len());
+            }
+            self.clone().report_updates(&paths, file_reports, Some(near_matches)).await;
+
+            let settings = crate::options().settings();
+            for page in groups.iter_mut().chain(teachers.iter_mut()) {
+                settings.bells.apply(page);
+            }
+
             let group_changes = compare::schedule::Page::compare(
                 self.last.groups.read().await.as_ref().map(|page| (**page).clone()),
                 groups.as_ref().cloned()
@@ -1,21 +1,47 @@
 use std::{sync::Arc, path::PathBuf};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use log::warn;
 use palette::IntoColor;
 use serde_derive::{Serialize, Deserialize};
-use crate::{SyncResult, data::json::{
+use crate::{SyncResult, data::{regex, schedule::Bells, json::{
     self,
     Saving,
-    Loading
-}};
+    Loading,
+    DirectLoading
+}}};
+
+
+/// # Compile the patterns, warning if the group one
+/// can't give group codes to filter by
+fn validate_regex(patterns: &regex::Patterns) -> Result<(), ::regex::Error> {
+    let container = regex::Container::from_patterns(patterns)?;
+    let missing = container.missing_group_captures();
+
+    if !missing.is_empty() {
+        warn!(
+            "group pattern has no {:?} named groups, groups won't have codes to filter by",
+            missing
+        );
+    }
+
+    Ok(())
+}
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Server {
-    pub address: String
+    pub address: String,
+    /// # Bearer token for privileged endpoints
+    /// If not set, they are disabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>
 }
 impl Server {
     fn default() -> Self {
         Self {
-            address: "0.0.0.0:8080".to_string()
+            address: "0.0.0.0:8080".to_string(),
+            token: None
         }
     }
 }
@@ -84,24 +110,55 @@ pub struct MiddleParsing {
 pub struct Settings {
     path: PathBuf,
     pub server: Server,
-    pub parsing: Parsing
+    pub parsing: Parsing,
+    pub regex: regex::Patterns,
+    pub bells: Bells,
+    pub timezone: Option<Tz>
 }
 impl Settings {
     fn default(path: PathBuf) -> Arc<Self> {
         let this = Self {
             path,
             server: Server::default(),
-            parsing: Parsing::default()
+            parsing: Parsing::default(),
+            regex: regex::Patterns::default(),
+            bells: Bells::default(),
+            timezone: None
         };
 
         Arc::new(this)
     }
 
+    /// # Load, returning an error instead of panicking
+    /// if some value is invalid
+    pub async fn try_load(path: PathBuf) -> SyncResult<Arc<Self>> {
+        let middle = MiddleSettings::load(path.clone()).await?;
+        let parsing = Parsing::from_hex(
+            middle.parsing.fulltime_color.clone(),
+            middle.parsing.remote_color.clone()
+        )?;
+        validate_regex(&middle.regex)?;
+        middle.bells.validate()?;
+
+        let this = Self {
+            path,
+            server: middle.server.clone(),
+            parsing,
+            regex: middle.regex.clone(),
+            bells: middle.bells.clone(),
+            timezone: middle.timezone
+        };
+
+        Ok(Arc::new(this))
+    }
+
     pub async fn load_or_init(path: PathBuf) -> SyncResult<Arc<Self>> {
         let this;
 
         if path.exists() {
             this = Self::load(path).await?;
+            validate_regex(&this.regex)?;
+            this.bells.validate()?;
         } else {
             this = Self::default(path);
             this.clone().save().await?;
@@ -109,13 +166,46 @@ impl Settings {
 
         Ok(this)
     }
+
+    /// # `utc` in `timezone`, or in the system one if not set
+    pub fn to_local(&self, utc: NaiveDateTime) -> NaiveDateTime {
+        let utc = DateTime::<Utc>::from_naive_utc_and_offset(utc, Utc);
+
+        match self.timezone {
+            Some(tz) => utc.with_timezone(&tz).naive_local(),
+            None => utc.with_timezone(&Local).naive_local()
+        }
+    }
+
+    /// # Reverse of `to_local`
+    /// Times skipped by a DST change are left as is.
+    pub fn to_utc(&self, local: NaiveDateTime) -> NaiveDateTime {
+        let utc = match self.timezone {
+            Some(tz) => tz.from_local_datetime(&local)
+                .earliest()
+                .map(|datetime| datetime.naive_utc()),
+            None => Local.from_local_datetime(&local)
+                .earliest()
+                .map(|datetime| datetime.naive_utc())
+        };
+
+        utc.unwrap_or(local)
+    }
+
+    /// # Current time in `timezone`
+    pub fn now(&self) -> NaiveDateTime {
+        self.to_local(Utc::now().naive_utc())
+    }
 }
 impl json::ToMiddle<MiddleSettings> for Settings {
     async fn to_middle(&self) -> MiddleSettings {
         MiddleSettings {
             path: self.path.clone(),
             server: self.server.clone(),
-            parsing: self.parsing.to_middle().await
+            parsing: self.parsing.to_middle().await,
+            regex: self.regex.clone(),
+            bells: self.bells.clone(),
+            timezone: self.timezone
         }
     }
 }
@@ -129,7 +219,10 @@ impl json::FromMiddle<MiddleSettings> for Settings {
             // and idiot and autism
             parsing: (*Parsing::from_middle(
                 Arc::new(middle.parsing.clone())
-            )).clone()
+            )).clone(),
+            regex: middle.regex.clone(),
+            bells: middle.bells.clone(),
+            timezone: middle.timezone
         };
 
         Arc::new(this)
@@ -144,7 +237,17 @@ pub struct MiddleSettings {
     #[serde(skip)]
     path: PathBuf,
     pub server: Server,
-    pub parsing: MiddleParsing
+    pub parsing: MiddleParsing,
+    /// # Overrides of the default patterns
+    #[serde(default, skip_serializing_if = "regex::Patterns::is_empty")]
+    pub regex: regex::Patterns,
+    /// # Lesson times of each day
+    #[serde(default, skip_serializing_if = "Bells::is_empty")]
+    pub bells: Bells,
+    /// # IANA name like `Europe/Moscow`,
+    /// the system timezone is used if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<Tz>
 }
 impl json::Path for MiddleSettings {
     fn path(&self) -> PathBuf {
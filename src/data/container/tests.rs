@@ -0,0 +1,153 @@
+//! # Whole pipeline against a local HTTP server
+//! Fetching, unpacking, parsing, merging, comparing
+//! and the `Notify` sent to WebSocket clients.
+
+use async_zip::{base::write::ZipFileWriter, Compression, ZipEntryBuilder};
+use bytes::Bytes;
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+use std::{net::SocketAddr, sync::{Arc, Mutex}};
+use tempfile::TempDir;
+use crate::data::schedule::Notify;
+use super::schedule::Schedule;
+
+
+const GROUP: &str = "1КРД1";
+
+fn sheet(subject: &str) -> String {
+    format!(
+        r#"<meta charset="utf-8"><style type="text/css">.s1{{background-color:#fce5cd;}}</style>
+<div class="ritz grid-container"><table><tbody>
+<tr><td></td><td>пн 01.09.25</td></tr>
+<tr><td>{}</td><td class="s1">{} Иванова А.А. 101</td></tr>
+</tbody></table></div>"#,
+        GROUP,
+        subject
+    )
+}
+
+/// # ZIP export like the one Google Sheets gives
+async fn export(html: &str) -> Bytes {
+    let mut writer = ZipFileWriter::new(vec![]);
+    let entry = ZipEntryBuilder::new("Sheet1.html".to_string().into(), Compression::Deflate);
+    writer.write_entry_whole(entry, html.as_bytes()).await.unwrap();
+    writer.close().await.unwrap().into()
+}
+
+/// # Serve `content` to every request
+/// Just enough HTTP for `reqwest`.
+async fn serve(content: Arc<Mutex<Bytes>>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else { continue };
+            let content = content.clone();
+
+            tokio::spawn(async move {
+                let mut request = vec![];
+                let mut buf = [0; 1024];
+
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(read) => request.extend_from_slice(&buf[..read])
+                    }
+                }
+
+                let body = content.lock().unwrap().clone();
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    Content-Type: application/zip\r\n\
+                    Content-Length: {}\r\n\
+                    Connection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&body).await;
+            });
+        }
+    });
+
+    addr
+}
+
+/// # Data directory with a single groups schedule
+/// Removed when dropped
+async fn data_dir(addr: SocketAddr) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let schedule_dir = dir.path().join("schedule");
+    tokio::fs::create_dir_all(&schedule_dir).await.unwrap();
+
+    let index = serde_json::json!({
+        "fetch": true,
+        "updated": "1970-01-01T00:00:00",
+        "period": { "secs": 600, "nanos": 0 },
+        "ignored": [],
+        "archive": { "enabled": false },
+        "types": [{
+            "kind": "groups",
+            "name": "groups",
+            "url": format!("http://{}/export?format=zip", addr),
+            "fetch_timeout": { "secs": 10, "nanos": 0 },
+            "retry_period": { "secs": 1, "nanos": 0 },
+            "backoff": { "max_attempts": 1 }
+        }]
+    });
+    tokio::fs::write(
+        schedule_dir.join("index.json"),
+        serde_json::to_vec(&index).unwrap()
+    ).await.unwrap();
+
+    dir
+}
+
+async fn subject_names(schedule: &Schedule) -> Vec<String> {
+    let groups = schedule.last.groups.read().await.clone().unwrap();
+    let formation = groups.formations.iter().find(|form| form.name == GROUP).unwrap();
+
+    formation.days
+        .iter()
+        .flat_map(|day| day.subjects.iter())
+        .map(|subject| subject.name.clone())
+        .collect()
+}
+
+fn notify(schedule: &Arc<Schedule>) -> Arc<Notify> {
+    schedule.clone().get_notify_rx().borrow().clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pipeline() {
+    crate::init_test_globals().await;
+
+    let content = Arc::new(Mutex::new(export(&sheet("Химия")).await));
+    let addr = serve(content.clone()).await;
+    let dir = data_dir(addr).await;
+
+    let schedule = Schedule::default_from_dir(dir.path().join("schedule")).await.unwrap();
+
+    // first fetch, everything appears
+    schedule.index().update_all(None).await;
+
+    assert_eq!(subject_names(&schedule).await, vec!["Химия"]);
+    let first = notify(&schedule);
+    let appeared = &first.groups.as_ref().unwrap().formations.appeared;
+    assert!(appeared.iter().any(|form| form.name == GROUP));
+
+    // same export, nothing to notify about
+    schedule.index().update_all(None).await;
+
+    assert_eq!(notify(&schedule).random, first.random);
+
+    // sheet changed between two fetches
+    *content.lock().unwrap() = export(&sheet("Физика")).await;
+    schedule.index().update_all(None).await;
+
+    assert_eq!(subject_names(&schedule).await, vec!["Физика"]);
+    let second = notify(&schedule);
+    assert_ne!(second.random, first.random);
+    let changes = &second.groups.as_ref().unwrap().formations;
+    assert!(changes.appeared.is_empty());
+    assert!(changes.changed.iter().any(|form| form.name.as_deref() == Some(GROUP)));
+}
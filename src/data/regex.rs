@@ -1,7 +1,61 @@
 use regex::Regex;
+use serde_derive::{Serialize, Deserialize};
 use std::sync::Arc;
 
 
+const GROUP: &str = r"(?P<course>[0-9])([-.]|\s)*(?P<speciality>[а-яёА-ЯЁ]{2,3})([-.]|\s)*(?P<number>[0-9]{1,2})";
+const WEEKDAY: &str = r"\b([пП][нН]|[вВ][тТ]|[сС][рР]|[чЧ][тТ]|[пП][тТ]|[сС][бБ]|[вВ][сС])\b";
+const DATE: &str = r"(\d{1,2})\W(\d{1,2})(\W(\d{4}|\d{2}))*";
+const TEACHER: &str = r"([А-ЯЁ][а-яё]{1,})([^а-яёА-ЯЁa-zA-Z0-9_])([А-ЯЁ]{1}[.])\s*([А-ЯЁ]{1}[.]?)?";
+const VACANCY: &str = r"([А-ЯЁ][а-яё]{5,9})([^а-яёА-ЯЁa-zA-Z0-9_])(\d{1,3})([^а-яёА-ЯЁa-zA-Z0-9_]+\d+)?";
+const STATUS: &str = r"(?i)[(\[]?\s*\b(?:(?P<cancelled>отмен[а-яё]*)|(?P<replacement>замен[а-яё]*)|(?P<moved>перен[оеё]с[а-яё]*)(?:\s+(?P<direction>на|со?)\b(?:\s*\d{1,2}\.\d{1,2}(?:\.\d{2,4})?)?)?)\s*[)\]]?\s*[:\-–—]?";
+const SUBGROUP: &str = r"(?i)[(\[]?\s*\b(?:(?P<before>[1-9])\s*-?\s*(?:я|ая)?\s*(?:подгрупп[а-яё]*|подгр\.?|п/г|пг)|(?:подгрупп[а-яё]*|подгр\.?|п/г|пг)\s*(?P<after>[1-9]))(?:\b|\s|$)\s*[)\]]?";
+const ROOM_KIND: &str = r"(?i)(?P<sport>спорт)|(?P<assembly>акт)|(?P<remote>дист|онлайн|zoom|сдо)";
+const ROOM_BUILDING: &str = r"(?i)корп(?:ус)?\.?\s*(?P<building>\d+[а-яё]?)";
+const ROOM_FLOOR: &str = r"(?i)эт(?:аж)?\.?\s*(?P<floor>\d+)";
+const ROOM_FLOOR_REVERSE: &str = r"(?i)(?P<floor>\d+)\s*-?\s*(?:й\s*)?эт(?:аж)?\.?";
+const ROOM_NUMBER: &str = r"\d+[а-яёА-ЯЁa-zA-Z]?";
+const CABINET: &str = r"(((([кКK][аАaA][бБ])[^а-яёА-ЯЁa-zA-Z0-9_]*)?([\d?]{1,3})[а-яёА-ЯЁa-zA-Z]*)|((([сСcC][пП][оОoO][рРpP][тТ])|([аАaA][кК][тТ].*))([^а-яёА-ЯЁa-zA-Z0-9_]){0,3}[зЗ][аАaA][лЛ]?))+$";
+
+
+/// # Named groups of the group pattern
+/// that make up a group code
+pub const GROUP_CAPTURES: [&str; 3] = ["course", "speciality", "number"];
+
+/// # Patterns overridden in `settings.json`
+/// Those that aren't set use the defaults
+/// made for this college's naming.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Patterns {
+    /// # Group name, e.g. `4КРД36`
+    /// Needs the `GROUP_CAPTURES` named groups,
+    /// otherwise groups have no `code`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// # Teacher name, e.g. `Иванова А.А.`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub teacher: Option<String>,
+    /// # Teacher vacancy, e.g. `Вакансия 1`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vacancy: Option<String>,
+    /// # Cabinet at the end of a subject, e.g. `каб 101`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cabinet: Option<String>,
+    /// # Date, e.g. `01.09.25`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// # Short weekday, e.g. `пн`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<String>
+}
+impl Patterns {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+
 pub struct Container {
     /// ## Match examples
     /// - 4-КРД-36
@@ -77,41 +131,103 @@ pub struct Container {
     pub teacher: Arc<Regex>,
     pub vacancy: Arc<Regex>,
     pub end_cabinet: Arc<Regex>,
+    /// ## Match examples
+    /// - Химия `(отмена)`
+    /// - `ЗАМЕНА:` Химия
+    /// - Химия `перенос на 12.09`
+    /// - `перенос с 10.09` Химия
+    pub status: Arc<Regex>,
+    /// ## Match examples
+    /// - Химия `1 подгр.`
+    /// - Химия `(2-я подгруппа)`
+    /// - `п/г 1` Химия
+    pub subgroup: Arc<Regex>,
+    /// ## Match examples
+    /// - `спорт` зал
+    /// - `акт`овый зал
+    /// - `дист`анционно
+    pub room_kind: Arc<Regex>,
+    /// ## Match examples
+    /// - `корп. 2` каб 101
+    pub room_building: Arc<Regex>,
+    /// ## Match examples
+    /// - каб 301 `эт. 3`
+    pub room_floor: Arc<Regex>,
+    /// # Checked if `room_floor` doesn't match,
+    /// it would take the room number in `301 эт. 3`
+    /// 
+    /// ## Match examples
+    /// - `3 этаж` каб 301
+    pub room_floor_reverse: Arc<Regex>,
+    pub room_number: Arc<Regex>,
     pub nonword: Arc<Regex>,
     pub digit: Arc<Regex>,
     pub start_digits: Arc<Regex>,
-    pub end_digits: Arc<Regex>
+    pub end_digits: Arc<Regex>,
+    /// # Insert the missing `К` into group names
+    /// like `4РД36`, only for the default `group` pattern
+    pub complete_group: bool
 }
 impl Default for Container {
     fn default() -> Container {
-        let group = r"([0-9])([-.]|\s)*([а-яёА-ЯЁ]{2,3})([-.]|\s)*([0-9]{1,2})";
-        let start_group = format!(r"^{}", group);
+        Self::from_patterns(&Patterns::default()).unwrap()
+    }
+}
+impl Container {
+    /// # Compile the patterns, using the default
+    /// ones where nothing is overridden
+    pub fn from_patterns(patterns: &Patterns) -> Result<Container, regex::Error> {
+        let group = patterns.group.as_deref().unwrap_or(GROUP);
+        let start_group = format!(r"^(?:{})", group);
         let start_attender_sep = r"^\s*[/\\]\s*";
         let end_attender_sep = r"(\s*[/\\]\s*)+$";
-        let whole_short_weekday = r"\b([пП][нН]|[вВ][тТ]|[сС][рР]|[чЧ][тТ]|[пП][тТ]|[сС][бБ]|[вВ][сС])\b";
-        let date = r"(\d{1,2})\W(\d{1,2})(\W(\d{4}|\d{2}))*";
-        let teacher = r"([А-ЯЁ][а-яё]{1,})([^а-яёА-ЯЁa-zA-Z0-9_])([А-ЯЁ]{1}[.])\s*([А-ЯЁ]{1}[.]?)?";
-        let vacancy = r"([А-ЯЁ][а-яё]{5,9})([^а-яёА-ЯЁa-zA-Z0-9_])(\d{1,3})([^а-яёА-ЯЁa-zA-Z0-9_]+\d+)?";
-        let end_cabinet = r"(((([кКK][аАaA][бБ])[^а-яёА-ЯЁa-zA-Z0-9_]*)?([\d?]{1,3})[а-яёА-ЯЁa-zA-Z]*)|((([сСcC][пП][оОoO][рРpP][тТ])|([аАaA][кК][тТ].*))([^а-яёА-ЯЁa-zA-Z0-9_]){0,3}[зЗ][аАaA][лЛ]?))+$";
+        let whole_short_weekday = patterns.weekday.as_deref().unwrap_or(WEEKDAY);
+        let date = patterns.date.as_deref().unwrap_or(DATE);
+        let teacher = patterns.teacher.as_deref().unwrap_or(TEACHER);
+        let vacancy = patterns.vacancy.as_deref().unwrap_or(VACANCY);
+        let end_cabinet = patterns.cabinet.as_deref().unwrap_or(CABINET);
         let nonword = r"[^а-яёА-ЯЁa-zA-Z0-9_]";
         let digit = r"\d";
         let start_digits = r"^\d+";
         let end_digits = r"\d+$";
 
-        Container {
-            group: Arc::new(Regex::new(group).unwrap()), 
-            start_group: Arc::new(Regex::new(&start_group).unwrap()),
-            start_attender_sep: Arc::new(Regex::new(start_attender_sep).unwrap()),
-            end_attender_sep: Arc::new(Regex::new(end_attender_sep).unwrap()),
-            whole_short_weekday: Arc::new(Regex::new(whole_short_weekday).unwrap()), 
-            date: Arc::new(Regex::new(date).unwrap()), 
-            teacher: Arc::new(Regex::new(teacher).unwrap()),
-            vacancy: Arc::new(Regex::new(vacancy).unwrap()),
-            end_cabinet: Arc::new(Regex::new(end_cabinet).unwrap()),
-            nonword: Arc::new(Regex::new(nonword).unwrap()),
-            digit: Arc::new(Regex::new(digit).unwrap()),
-            start_digits: Arc::new(Regex::new(start_digits).unwrap()),
-            end_digits: Arc::new(Regex::new(end_digits).unwrap())
-        }
+        let this = Container {
+            group: Arc::new(Regex::new(group)?), 
+            start_group: Arc::new(Regex::new(&start_group)?),
+            start_attender_sep: Arc::new(Regex::new(start_attender_sep)?),
+            end_attender_sep: Arc::new(Regex::new(end_attender_sep)?),
+            whole_short_weekday: Arc::new(Regex::new(whole_short_weekday)?), 
+            date: Arc::new(Regex::new(date)?), 
+            teacher: Arc::new(Regex::new(teacher)?),
+            vacancy: Arc::new(Regex::new(vacancy)?),
+            end_cabinet: Arc::new(Regex::new(end_cabinet)?),
+            status: Arc::new(Regex::new(STATUS)?),
+            subgroup: Arc::new(Regex::new(SUBGROUP)?),
+            room_kind: Arc::new(Regex::new(ROOM_KIND)?),
+            room_building: Arc::new(Regex::new(ROOM_BUILDING)?),
+            room_floor: Arc::new(Regex::new(ROOM_FLOOR)?),
+            room_floor_reverse: Arc::new(Regex::new(ROOM_FLOOR_REVERSE)?),
+            room_number: Arc::new(Regex::new(ROOM_NUMBER)?),
+            nonword: Arc::new(Regex::new(nonword)?),
+            digit: Arc::new(Regex::new(digit)?),
+            start_digits: Arc::new(Regex::new(start_digits)?),
+            end_digits: Arc::new(Regex::new(end_digits)?),
+            complete_group: patterns.group.is_none()
+        };
+
+        Ok(this)
+    }
+
+    /// # Names of `GROUP_CAPTURES` the group pattern doesn't have
+    pub fn missing_group_captures(&self) -> Vec<&'static str> {
+        let names = self.group
+            .capture_names()
+            .flatten()
+            .collect::<Vec<&str>>();
+
+        GROUP_CAPTURES
+            .into_iter()
+            .filter(|capture| !names.contains(capture))
+            .collect()
     }
 }
\ No newline at end of file
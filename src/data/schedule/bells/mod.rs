@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use serde_derive::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use crate::data::{schedule::Page, Weekday};
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum BellsError {
+    #[error("unknown bells preset {0}")]
+    UnknownPreset(String),
+    #[error("subject {1} of bells preset {0} ends before it starts")]
+    EndBeforeStart(String, u32)
+}
+
+/// # When a subject starts and ends
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Bell {
+    pub start: NaiveTime,
+    pub end: NaiveTime
+}
+
+/// # Lesson times
+/// A preset maps every subject number to its times.
+/// The preset of a day is taken from `dates` first,
+/// then from `weekdays`, days without one get no times.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Bells {
+    pub presets: BTreeMap<String, BTreeMap<u32, Bell>>,
+    pub weekdays: BTreeMap<Weekday, String>,
+    /// # Shortened days and other exceptions
+    pub dates: BTreeMap<NaiveDate, String>
+}
+impl Bells {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), BellsError> {
+        let names = self.weekdays.values().chain(self.dates.values());
+        for name in names {
+            if !self.presets.contains_key(name) {
+                return Err(BellsError::UnknownPreset(name.clone()))
+            }
+        }
+
+        for (name, preset) in self.presets.iter() {
+            for (num, bell) in preset.iter() {
+                if bell.end < bell.start {
+                    return Err(BellsError::EndBeforeStart(name.clone(), *num))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn preset(&self, date: NaiveDate) -> Option<&BTreeMap<u32, Bell>> {
+        let name = self.dates.get(&date).or_else(
+            || self.weekdays.get(&Weekday::from_chrono(&date.weekday()))
+        )?;
+
+        self.presets.get(name)
+    }
+
+    /// # Set `start` and `end` of every subject
+    pub fn apply(&self, page: &mut Page) {
+        for formation in page.formations.iter_mut() {
+            for day in formation.days.iter_mut() {
+                let preset = self.preset(day.date);
+
+                for subject in day.subjects.iter_mut() {
+                    let bell = preset.and_then(|preset| preset.get(&subject.num));
+                    subject.start = bell.map(|bell| bell.start);
+                    subject.end = bell.map(|bell| bell.end);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use super::*;
+
+
+fn date(day: u32) -> NaiveDate {
+    // 2024-09-02 is a Monday
+    NaiveDate::from_ymd_opt(2024, 9, day).unwrap()
+}
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn bells() -> Bells {
+    serde_json::from_value(serde_json::json!({
+        "presets": {
+            "regular": {
+                "1": { "start": "08:30", "end": "10:00" },
+                "2": { "start": "10:10", "end": "11:40" }
+            },
+            "short": {
+                "1": { "start": "08:30", "end": "09:30" },
+                "2": { "start": "09:40", "end": "10:40" }
+            }
+        },
+        "weekdays": {
+            "Monday": "regular",
+            "Tuesday": "regular"
+        },
+        "dates": {
+            "2024-09-03": "short"
+        }
+    })).unwrap()
+}
+
+#[test]
+fn test_preset() {
+    let bells = bells();
+
+    assert_eq!(bells.preset(date(2)).unwrap()[&2].start, time(10, 10));
+    // date overrides the weekday
+    assert_eq!(bells.preset(date(3)).unwrap()[&2].start, time(9, 40));
+    // no preset on wednesdays
+    assert!(bells.preset(date(4)).is_none());
+}
+
+#[test]
+fn test_validate() {
+    let mut bells = bells();
+    assert!(bells.validate().is_ok());
+
+    bells.weekdays.insert(Weekday::Friday, "holiday".to_string());
+    assert!(matches!(bells.validate(), Err(BellsError::UnknownPreset(_))));
+
+    let mut bells = self::bells();
+    bells.presets.get_mut("short").unwrap().insert(3, Bell {
+        start: time(12, 0),
+        end: time(11, 0)
+    });
+    assert!(matches!(bells.validate(), Err(BellsError::EndBeforeStart(_, 3))));
+}
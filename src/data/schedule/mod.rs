@@ -1,21 +1,88 @@
 mod file;
 mod notify;
 mod last;
+pub mod report;
+pub mod bells;
+pub mod now;
 pub mod raw;
 pub mod attender;
 
 pub use file::File;
 pub use notify::Notify;
 pub use last::Last;
+pub use report::Report;
+pub use bells::Bells;
+pub use now::Now;
 
 use crate::{compare::FindingCmp, regexes};
 
 use serde_derive::{Serialize, Deserialize};
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 use derivative::Derivative;
 use std::ops::RangeInclusive;
 
 
+/// # What a room is used for
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomKind {
+    Classroom,
+    SportHall,
+    AssemblyHall,
+    Remote,
+    #[default]
+    Unknown
+}
+
+/// # Single room parsed from a `Cabinet` value
+/// `"корп. 2 каб 101"` is room `101` in building `2`
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash
+)]
+pub struct Room {
+    pub raw: String,
+    pub kind: RoomKind,
+    pub number: Option<String>,
+    pub building: Option<String>,
+    pub floor: Option<u32>
+}
+impl Room {
+    /// # Do both point to the same place
+    /// Rooms are compared by number, and by building
+    /// if both have it. Rooms without a number,
+    /// like a sport hall, are compared by kind.
+    pub fn is_same_with(&self, other: &Room) -> bool {
+        match (&self.number, &other.number) {
+            (Some(number), Some(other_number)) => {
+                let is_other_building = matches!(
+                    (&self.building, &other.building),
+                    (Some(building), Some(other_building)) if building != other_building
+                );
+                number == other_number && !is_other_building
+            },
+            (None, None) if self.kind != RoomKind::Unknown => self.kind == other.kind,
+            (None, None) => self.raw.to_lowercase() == other.raw.to_lowercase(),
+            _ => false
+        }
+    }
+}
+
 /// # A cabinet in `Attender`
 #[derive(Derivative)]
 #[derivative(Hash)]
@@ -41,7 +108,15 @@ pub struct Cabinet {
     /// If this instance belongs to a group schedule,
     /// `opposite` would reference a cabinet found in
     /// teacher's schedule.
-    pub opposite: Option<String>
+    pub opposite: Option<String>,
+    /// # Rooms parsed from `primary`
+    #[derivative(Hash="ignore")]
+    #[serde(default)]
+    pub primary_rooms: Vec<Room>,
+    /// # Rooms parsed from `opposite`
+    #[derivative(Hash="ignore")]
+    #[serde(default)]
+    pub opposite_rooms: Vec<Room>
 }
 impl FindingCmp for Cabinet {
     fn is_partially_same_with(&self, other: &Self) -> bool {
@@ -53,7 +128,9 @@ impl Default for Cabinet {
         Self {
             recovered: false,
             primary: None,
-            opposite: None
+            opposite: None,
+            primary_rooms: vec![],
+            opposite_rooms: vec![]
         }
     }
 }
@@ -64,6 +141,13 @@ impl Cabinet {
 
     pub fn swap(&mut self) {
         std::mem::swap(&mut self.primary, &mut self.opposite);
+        std::mem::swap(&mut self.primary_rooms, &mut self.opposite_rooms);
+    }
+
+    /// # Set `opposite` to the `primary` of `other`
+    pub fn set_opposite_from(&mut self, other: &Cabinet) {
+        self.opposite = other.primary.clone();
+        self.opposite_rooms = other.primary_rooms.clone();
     }
 
     pub fn swapped(mut self) -> Self {
@@ -88,6 +172,9 @@ pub struct Attender {
     pub recovered: bool,
     pub kind: attender::Kind,
     pub name: String,
+    /// # Same as `subgroup` of the `Subject`
+    #[serde(default)]
+    pub subgroup: Option<u32>,
     pub cabinet: Cabinet
 }
 impl FindingCmp for Attender {
@@ -97,6 +184,36 @@ impl FindingCmp for Attender {
     }
 }
 
+/// # What happens to a `Subject`
+/// Detected from markers like `отмена` in the cell
+/// or from struck through cells.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    Regular,
+    /// # `отмена` or struck through
+    Cancelled,
+    /// # `замена`, another subject or teacher
+    Replacement,
+    /// # `перенос на 12.09`, the subject was here
+    /// but goes elsewhere
+    MovedTo,
+    /// # `перенос с 10.09`, the subject
+    /// came here from elsewhere
+    MovedFrom
+}
+
 /// # Single subject (lesson) in a `Day`
 #[derive(Derivative)]
 #[derivative(Hash)]
@@ -113,17 +230,49 @@ pub struct Subject {
     pub recovered: bool,
     pub name: String,
     pub num: u32,
+    /// # Part of the group(s) it's for,
+    /// `None` for the whole group
+    #[serde(default)]
+    pub subgroup: Option<u32>,
     pub format: raw::Format,
-    pub attenders: Vec<Attender>
+    #[serde(default)]
+    pub status: Status,
+    pub attenders: Vec<Attender>,
+    /// # Bell times of `num` on this day,
+    /// from `bells` in settings
+    #[derivative(Hash="ignore")]
+    #[serde(default)]
+    pub start: Option<NaiveTime>,
+    #[derivative(Hash="ignore")]
+    #[serde(default)]
+    pub end: Option<NaiveTime>
 }
 impl FindingCmp for Subject {
     fn is_partially_same_with(&self, other: &Self) -> bool {
         self.name == other.name &&
         //self.num == other.num &&
+        self.subgroup == other.subgroup &&
         self.format == other.format
     }
 }
 impl Subject {
+    /// # Set `subgroup` of the subject and its attenders
+    pub fn set_subgroup(&mut self, subgroup: Option<u32>) {
+        self.subgroup = subgroup;
+        for attender in self.attenders.iter_mut() {
+            attender.subgroup = subgroup;
+        }
+    }
+
+    /// # Can both be the same lesson
+    /// A subject without a subgroup may be one
+    /// the other schedule split into subgroups.
+    pub fn is_same_subgroup_with(&self, subgroup: Option<u32>) -> bool {
+        self.subgroup.is_none() ||
+        subgroup.is_none() ||
+        self.subgroup == subgroup
+    }
+
     pub fn in_numbered_filler(&self) -> bool {
         if self.raw.len() != 1 {
             return false;
@@ -154,6 +303,34 @@ impl FindingCmp for Day {
     }
 }
 
+/// # Parts of a group name
+/// `4КРД36` is course `4`,
+/// speciality `КРД` and number `36`
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash
+)]
+pub struct GroupCode {
+    pub course: u32,
+    pub speciality: String,
+    pub number: u32
+}
+impl GroupCode {
+    /// # `None` matches anything,
+    /// speciality is compared ignoring case
+    pub fn matches(&self, course: Option<u32>, speciality: Option<&str>) -> bool {
+        course.is_none_or(|course| course == self.course) &&
+        speciality.is_none_or(|speciality| {
+            speciality.to_uppercase() == self.speciality.to_uppercase()
+        })
+    }
+}
+
 /// # Group or teacher mapping
 #[derive(Derivative)]
 #[derivative(Hash)]
@@ -167,7 +344,17 @@ pub struct Formation {
     #[derivative(Hash="ignore")]
     pub raw: String,
     pub recovered: bool,
+    /// # Taken from the previous update
+    /// because its file failed to parse this time
+    #[derivative(Hash="ignore")]
+    #[serde(default)]
+    pub stale: bool,
     pub name: String,
+    /// # Only for groups, if the name
+    /// matches the `group` pattern
+    #[derivative(Hash="ignore")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<GroupCode>,
     pub days: Vec<Day>,
 }
 impl FindingCmp for Formation {
@@ -191,4 +378,10 @@ impl Page {
             self.formations.remove(index);
         }
     }
+
+    pub fn mark_stale(&mut self) {
+        for formation in self.formations.iter_mut() {
+            formation.stale = true;
+        }
+    }
 }
@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_derive::Serialize;
+use crate::data::schedule::{Page, Room, Status, Subject};
+
+
+/// # Whose lessons to look for
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    /// # Formation name in a groups or teachers page
+    Formation(&'a str),
+    /// # Room in `primary` cabinet of any attender
+    Cabinet(&'a Room)
+}
+
+/// # A subject with its day and formation
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Lesson {
+    pub formation: String,
+    pub date: NaiveDate,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub subject: Subject
+}
+
+/// # Current and next lessons at `time`
+/// Only subjects with `start` and `end` from `bells`
+/// are taken into account. There may be several
+/// lessons at once, e.g. of two subgroups.
+#[derive(Serialize, Debug, Clone)]
+pub struct Now {
+    /// # Local time this was evaluated at
+    pub time: NaiveDateTime,
+    pub current: Vec<Lesson>,
+    /// # Lessons with the nearest start after `time`
+    pub next: Vec<Lesson>,
+    /// # Time left until `next` starts
+    pub until: Option<std::time::Duration>
+}
+impl Now {
+    pub fn find(page: &Page, target: Target, time: NaiveDateTime) -> Self {
+        let mut lessons = Self::lessons(page, target);
+        lessons.sort_by_key(|lesson| lesson.start);
+
+        let current = lessons
+            .iter()
+            .filter(|lesson| lesson.start <= time && time < lesson.end)
+            .cloned()
+            .collect::<Vec<Lesson>>();
+        let next_start = lessons
+            .iter()
+            .map(|lesson| lesson.start)
+            .find(|start| time < *start);
+        let next = lessons
+            .into_iter()
+            .filter(|lesson| Some(lesson.start) == next_start)
+            .collect::<Vec<Lesson>>();
+        let until = next_start.and_then(|start| (start - time).to_std().ok());
+
+        Self { time, current, next, until }
+    }
+
+    fn lessons(page: &Page, target: Target) -> Vec<Lesson> {
+        let mut lessons = vec![];
+
+        for formation in page.formations.iter() {
+            if let Target::Formation(name) = target {
+                if formation.name != name {
+                    continue;
+                }
+            }
+
+            for day in formation.days.iter() {
+                for subject in day.subjects.iter() {
+                    // these don't take place here
+                    if matches!(subject.status, Status::Cancelled | Status::MovedTo) {
+                        continue;
+                    }
+
+                    if let Target::Cabinet(room) = target {
+                        let is_there = subject.attenders.iter()
+                            .flat_map(|attender| attender.cabinet.primary_rooms.iter())
+                            .any(|other| other.is_same_with(room));
+                        if !is_there {
+                            continue;
+                        }
+                    }
+
+                    let (Some(start), Some(end)) = (subject.start, subject.end) else {
+                        continue
+                    };
+
+                    let lesson = Lesson {
+                        formation: formation.name.clone(),
+                        date: day.date,
+                        start: day.date.and_time(start),
+                        end: day.date.and_time(end),
+                        subject: subject.clone()
+                    };
+
+                    // same lesson of several groups in one cabinet
+                    let is_duplicate = matches!(target, Target::Cabinet(_))
+                        && lessons.iter().any(|other: &Lesson| other.start == lesson.start);
+                    if !is_duplicate {
+                        lessons.push(lesson);
+                    }
+                }
+            }
+        }
+
+        lessons
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::NaiveTime;
+use crate::data::schedule::{attender, raw, Attender, Cabinet, Day, Formation, RoomKind};
+use super::*;
+
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 9, 2).unwrap()
+}
+
+fn at(hour: u32, minute: u32) -> NaiveDateTime {
+    date().and_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn room(number: &str) -> Room {
+    Room {
+        raw: number.to_string(),
+        kind: RoomKind::Classroom,
+        number: Some(number.to_string()),
+        building: None,
+        floor: None
+    }
+}
+
+fn subject(num: u32, name: &str, cabinet: &str) -> Subject {
+    let start = NaiveTime::from_hms_opt(7 + num * 2, 0, 0).unwrap();
+
+    Subject {
+        raw: name.to_string(),
+        recovered: false,
+        name: name.to_string(),
+        num,
+        subgroup: None,
+        format: raw::Format::Fulltime,
+        status: Default::default(),
+        attenders: vec![Attender {
+            raw: "Иванова А.А.".to_string(),
+            recovered: false,
+            kind: attender::Kind::Teacher,
+            name: "Иванова А.А.".to_string(),
+            subgroup: None,
+            cabinet: Cabinet {
+                primary: Some(cabinet.to_string()),
+                primary_rooms: vec![room(cabinet)],
+                ..Default::default()
+            }
+        }],
+        start: Some(start),
+        end: Some(start + chrono::Duration::minutes(90))
+    }
+}
+
+/// 1КДД1 has 09:00 Химия in 101 and 11:00 Физика in 102,
+/// 1КДД2 has 11:00 Химия in 101
+fn page() -> Page {
+    page_with(vec![subject(1, "Химия", "101"), subject(2, "Физика", "102")])
+}
+
+fn page_with(first_subjects: Vec<Subject>) -> Page {
+    let formation = |name: &str, subjects: Vec<Subject>| Formation {
+        raw: name.to_string(),
+        recovered: false,
+        stale: false,
+        name: name.to_string(),
+        code: None,
+        days: vec![Day {
+            raw: String::new(),
+            recovered: false,
+            date: date(),
+            subjects
+        }]
+    };
+
+    Page {
+        kind: raw::Kind::Groups,
+        date: date()..=date(),
+        formations: vec![
+            formation("1КДД1", first_subjects),
+            formation("1КДД2", vec![subject(2, "Химия", "101")])
+        ]
+    }
+}
+
+#[test]
+fn test_formation() {
+    let page = page();
+
+    let now = Now::find(&page, Target::Formation("1КДД1"), at(9, 30));
+    assert_eq!(names(&now.current), vec!["Химия"]);
+    assert_eq!(names(&now.next), vec!["Физика"]);
+    assert_eq!(now.until, Some(std::time::Duration::from_secs(90 * 60)));
+
+    // break between lessons
+    let now = Now::find(&page, Target::Formation("1КДД1"), at(10, 45));
+    assert!(now.current.is_empty());
+    assert_eq!(names(&now.next), vec!["Физика"]);
+
+    // day is over
+    let now = Now::find(&page, Target::Formation("1КДД1"), at(13, 0));
+    assert!(now.current.is_empty());
+    assert!(now.next.is_empty());
+    assert!(now.until.is_none());
+}
+
+fn names(lessons: &[Lesson]) -> Vec<&str> {
+    lessons.iter().map(|lesson| lesson.subject.name.as_str()).collect()
+}
+
+#[test]
+fn test_subgroups() {
+    let mut first = subject(1, "Химия", "101");
+    first.subgroup = Some(1);
+    let mut second = subject(1, "Информатика", "103");
+    second.subgroup = Some(2);
+
+    let now = Now::find(&page_with(vec![first, second]), Target::Formation("1КДД1"), at(9, 30));
+    assert_eq!(names(&now.current), vec!["Химия", "Информатика"]);
+}
+
+#[test]
+fn test_cancelled() {
+    let mut cancelled = subject(1, "Химия", "101");
+    cancelled.status = Status::Cancelled;
+
+    let now = Now::find(&page_with(vec![cancelled]), Target::Formation("1КДД1"), at(8, 30));
+    assert!(now.current.is_empty());
+    assert!(now.next.is_empty());
+}
+
+#[test]
+fn test_cabinet() {
+    let mut query = room("101");
+    query.raw = "каб 101".to_string();
+
+    let now = Now::find(&page(), Target::Cabinet(&query), at(9, 30));
+    assert_eq!(now.current.iter().map(|lesson| lesson.formation.as_str()).collect::<Vec<&str>>(), vec!["1КДД1"]);
+    assert_eq!(now.next.iter().map(|lesson| lesson.formation.as_str()).collect::<Vec<&str>>(), vec!["1КДД2"]);
+
+    let mut other_building = query.clone();
+    other_building.building = Some("2".to_string());
+    let mut in_building = page();
+    in_building.formations[0].days[0].subjects[0].attenders[0].cabinet.primary_rooms[0].building = Some("1".to_string());
+
+    let now = Now::find(&in_building, Target::Cabinet(&other_building), at(9, 30));
+    assert!(now.current.is_empty());
+}
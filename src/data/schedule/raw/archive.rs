@@ -0,0 +1,121 @@
+use chrono::{NaiveDateTime, Utc};
+use log::{debug, warn};
+use serde_derive::{Serialize, Deserialize};
+use std::{path::{Path, PathBuf}, time::Duration};
+
+
+/// # Nanoseconds keep two archives stored within
+/// the same second apart, they are optional when parsing
+/// so older names are still listed
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H-%M-%S%.f";
+
+/// # History of downloaded archives
+/// Every archive with new content is kept as
+/// `archive/<name>/<timestamp>.<extension>`
+/// next to `index.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Archive {
+    /// # Keep downloaded archives at all
+    pub enabled: bool,
+    /// # How many archives to keep for each schedule,
+    /// the oldest are removed first
+    pub max_count: Option<usize>,
+    /// # Remove archives older than this
+    pub max_age: Option<Duration>
+}
+impl Default for Archive {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_count: Some(500),
+            max_age: Some(Duration::from_secs(60 * 60 * 24 * 90))
+        }
+    }
+}
+impl Archive {
+    pub const DIR: &'static str = "archive";
+
+    /// # Save the archive and remove the expired ones
+    pub async fn store(
+        &self,
+        dir: &Path,
+        bytes: &[u8],
+        extension: &str
+    ) -> tokio::io::Result<Option<PathBuf>> {
+        if !self.enabled {
+            return Ok(None)
+        }
+
+        tokio::fs::create_dir_all(dir).await?;
+
+        let now = Utc::now().naive_utc();
+        let path = dir.join(format!(
+            "{}.{}",
+            now.format(TIMESTAMP_FORMAT),
+            extension
+        ));
+        tokio::fs::write(&path, bytes).await?;
+        debug!("archived {:?}", path);
+
+        self.prune(dir, now).await;
+
+        Ok(Some(path))
+    }
+
+    async fn prune(&self, dir: &Path, now: NaiveDateTime) {
+        let mut archived = Self::list(dir, None).await;
+
+        if let Some(max_age) = self.max_age.and_then(|age| chrono::Duration::from_std(age).ok()) {
+            // oldest first, so the expired ones are at the start
+            let expired = archived.iter().take_while(|(time, _)| now - *time > max_age).count();
+            for (_, path) in archived.drain(..expired) {
+                Self::remove(&path).await;
+            }
+        }
+
+        if let Some(max_count) = self.max_count {
+            let excess = archived.len().saturating_sub(max_count);
+            for (_, path) in archived.drain(..excess) {
+                Self::remove(&path).await;
+            }
+        }
+    }
+
+    async fn remove(path: &Path) {
+        debug!("removing expired archive {:?}", path);
+        if let Err(error) = tokio::fs::remove_file(path).await {
+            warn!("failed to remove expired archive {:?}: {:?}", path, error);
+        }
+    }
+
+    /// # Archives in `dir`, oldest first
+    /// If `extension` is set, other files are skipped.
+    pub async fn list(dir: &Path, extension: Option<&str>) -> Vec<(NaiveDateTime, PathBuf)> {
+        let mut archived = vec![];
+
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else { return archived };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+
+            if let Some(extension) = extension {
+                if path.extension() != Some(std::ffi::OsStr::new(extension)) {
+                    continue;
+                }
+            }
+
+            let Some(time) = path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| NaiveDateTime::parse_from_str(stem, TIMESTAMP_FORMAT).ok())
+            else {
+                continue
+            };
+
+            archived.push((time, path));
+        }
+
+        archived.sort();
+        archived
+    }
+}
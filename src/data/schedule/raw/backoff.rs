@@ -0,0 +1,52 @@
+use rand::Rng;
+use serde_derive::{Serialize, Deserialize};
+use std::time::Duration;
+
+
+/// # How to retry a failed fetch or unpack
+/// The first retry waits `retry_period` of the schedule,
+/// every next one waits `factor` times longer,
+/// but not longer than `max_period`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Backoff {
+    /// # Longest wait between two tries
+    pub max_period: Duration,
+    /// # How much longer every next wait is
+    pub factor: f64,
+    /// # Random deviation of every wait,
+    /// `0.2` means ±20%
+    pub jitter: f64,
+    /// # Give up after this many tries in one update
+    pub max_attempts: u32,
+    /// # Give up if retrying would last longer than this
+    pub deadline: Duration
+}
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            max_period: Duration::from_secs(60),
+            factor: 2.0,
+            jitter: 0.2,
+            max_attempts: 5,
+            deadline: Duration::from_secs(5 * 60)
+        }
+    }
+}
+impl Backoff {
+    /// # Wait before the try number `attempt` (starting from 1)
+    pub fn delay(&self, initial: Duration, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = (initial.as_secs_f64() * self.factor.max(1.0).powi(exponent))
+            .min(self.max_period.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let deviation = if jitter > 0.0 {
+            rand::thread_rng().gen_range(-jitter..=jitter)
+        } else {
+            0.0
+        };
+
+        Duration::from_secs_f64((secs * (1.0 + deviation)).max(0.0))
+    }
+}
@@ -2,5 +2,78 @@
 #[derive(Debug)]
 pub enum UnpackError {
     Io(tokio::io::Error),
-    Zip(async_zip::error::ZipError)
-}
\ No newline at end of file
+    Zip(async_zip::error::ZipError),
+    Limit(LimitError)
+}
+impl From<LimitError> for UnpackError {
+    fn from(value: LimitError) -> Self {
+        Self::Limit(value)
+    }
+}
+
+#[derive(Debug)]
+pub enum LimitError {
+    Entries { count: usize, max: usize },
+    EntrySize { name: String, max: u64 },
+    TotalSize { max: u64 }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    Http(reqwest::Error),
+    Io(tokio::io::Error),
+    Limit(LimitError)
+}
+impl From<reqwest::Error> for FetchError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Http(value)
+    }
+}
+impl From<tokio::io::Error> for FetchError {
+    fn from(value: tokio::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl From<LimitError> for FetchError {
+    fn from(value: LimitError) -> Self {
+        Self::Limit(value)
+    }
+}
+
+#[derive(Debug)]
+pub enum UpdateError {
+    Fetch(FetchError),
+    Unpack(UnpackError)
+}
+impl From<FetchError> for UpdateError {
+    fn from(value: FetchError) -> Self {
+        Self::Fetch(value)
+    }
+}
+impl From<UnpackError> for UpdateError {
+    fn from(value: UnpackError) -> Self {
+        Self::Unpack(value)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("index validation error")]
+pub enum IndexError {
+    ZeroPeriod,
+    ZeroSchedulePeriod(String),
+    /// # `types` is empty or all of them are `ignored`
+    NothingUpdated,
+    DuplicateName(String),
+    /// # `file://` URL without `source` points nowhere
+    MissingPath(String, std::path::PathBuf),
+    Http(String, HttpError)
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    Header(String),
+    Proxy(reqwest::Error),
+    Ca(std::io::Error),
+    Certificate(reqwest::Error),
+    Client(reqwest::Error)
+}
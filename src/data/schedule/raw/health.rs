@@ -0,0 +1,34 @@
+use chrono::{NaiveDateTime, Utc};
+use serde_derive::{Serialize, Deserialize};
+
+
+/// # How the last updates of a schedule went
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Health {
+    /// # When the schedule was last fetched
+    /// and unpacked without errors
+    pub last_success: Option<NaiveDateTime>,
+    /// # When the last error happened
+    pub last_failure: Option<NaiveDateTime>,
+    /// # Description of the last error
+    pub last_error: Option<String>,
+    /// # Tries that failed since the last success
+    pub consecutive_failures: u32
+}
+impl Health {
+    pub fn succeeded(&mut self) {
+        self.last_success = Some(Utc::now().naive_utc());
+        self.consecutive_failures = 0;
+    }
+
+    pub fn failed(&mut self, error: String) {
+        self.last_failure = Some(Utc::now().naive_utc());
+        self.last_error = Some(error);
+        self.consecutive_failures += 1;
+    }
+
+    pub fn is_failing(&self) -> bool {
+        self.consecutive_failures > 0
+    }
+}
@@ -0,0 +1,104 @@
+use reqwest::{header, redirect, Certificate, Proxy, RequestBuilder};
+use serde_derive::{Serialize, Deserialize};
+use std::{collections::BTreeMap, path::PathBuf};
+use crate::data::schedule::raw::error::HttpError;
+
+
+/// # Authorization sent with every request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Auth {
+    Basic {
+        username: String,
+        password: Option<String>
+    },
+    Bearer {
+        token: String
+    }
+}
+
+/// # HTTP client options of a source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Http {
+    /// # Sent with every request, e.g. `User-Agent`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+    /// # HTTP(S) proxy URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// # How many redirects to follow,
+    /// `0` to not follow them at all
+    pub max_redirects: usize,
+    /// # PEM file with extra root certificates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca: Option<PathBuf>
+}
+impl Default for Http {
+    fn default() -> Self {
+        Self {
+            headers: BTreeMap::new(),
+            auth: None,
+            proxy: None,
+            max_redirects: 10,
+            ca: None
+        }
+    }
+}
+impl Http {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn header_map(&self) -> Result<header::HeaderMap, HttpError> {
+        let mut headers = header::HeaderMap::new();
+
+        for (name, value) in self.headers.iter() {
+            let name = header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| HttpError::Header(name.clone()))?;
+            let value = header::HeaderValue::from_str(value)
+                .map_err(|_| HttpError::Header(name.to_string()))?;
+            headers.insert(name, value);
+        }
+
+        Ok(headers)
+    }
+
+    /// # Build a client with these options
+    pub fn client(&self, timeout: std::time::Duration) -> Result<reqwest::Client, HttpError> {
+        let policy = if self.max_redirects == 0 {
+            redirect::Policy::none()
+        } else {
+            redirect::Policy::limited(self.max_redirects)
+        };
+
+        let mut builder = reqwest::ClientBuilder::new()
+            .timeout(timeout)
+            .default_headers(self.header_map()?)
+            .redirect(policy);
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy).map_err(HttpError::Proxy)?);
+        }
+
+        if let Some(ca) = &self.ca {
+            let pem = std::fs::read(ca).map_err(HttpError::Ca)?;
+            for certificate in Certificate::from_pem_bundle(&pem).map_err(HttpError::Certificate)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        builder.build().map_err(HttpError::Client)
+    }
+
+    /// # Add `auth` to a request
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.auth {
+            Some(Auth::Basic { username, password }) => request.basic_auth(username, password.as_ref()),
+            Some(Auth::Bearer { token }) => request.bearer_auth(token),
+            None => request
+        }
+    }
+}
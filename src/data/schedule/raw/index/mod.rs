@@ -0,0 +1,1290 @@
+#[cfg(test)]
+mod tests;
+
+use log::{debug, warn};
+use chrono::{NaiveDateTime, DateTime, Utc, Duration};
+use async_zip::tokio::read::seek::ZipFileReader;
+use serde_derive::{Serialize, Deserialize};
+use reqwest::{self, header, StatusCode};
+use actix_web::web::Bytes;
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::AsyncReadExt, sync::{mpsc, Mutex, RwLock}, task::JoinHandle
+};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+use std::{
+    io::Cursor,
+    path::PathBuf,
+    sync::Arc,
+    time::SystemTime
+};
+use crate::{
+    SyncResult,
+    data::{
+        json::{
+            self,
+            Saving,
+            DirectLoading
+        },
+        schedule::{
+            raw::{Kind, Export, Source, Backoff, Health, Archive, Limits, Http, Policy, policy, error},
+            File
+        },
+    },
+    fs
+};
+
+pub enum UpdateFinishType {
+    /// # This call performed the update
+    Complete,
+    /// # Another update was already running,
+    /// returned after it finished
+    LockRelease {
+        /// # The other update included every schedule asked for
+        is_covered: bool
+    }
+}
+
+#[derive(Debug)]
+pub struct Index {
+    path: PathBuf,
+    updated_tx: mpsc::Sender<Vec<PathHolder>>,
+    converted_rx: Arc<RwLock<mpsc::Receiver<()>>>,
+    update_forever_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
+    /// # Schedules the last update under this lock
+    /// was for, `None` if it was for all of them
+    update_lock: Arc<Mutex<Option<Vec<String>>>>,
+    /// # Modification time of `index.json`
+    /// when it was last read or written by us
+    file_modified: Arc<RwLock<Option<SystemTime>>>,
+
+    pub fetch: bool,
+    pub updated: Arc<RwLock<NaiveDateTime>>,
+    pub period: Duration,
+    /// # Times of the day with their own period
+    pub windows: Vec<policy::Window>,
+    pub ignored: Vec<String>,
+    pub archive: Archive,
+    pub limits: Limits,
+    /// # Also write extracted files to disk,
+    /// only useful to look at them
+    pub write_unpacked: bool,
+    pub types: Vec<Arc<Schedule>>
+}
+impl json::Path for Index {
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+}
+impl Index {
+    fn default(
+        path: PathBuf,
+        updated_tx: mpsc::Sender<Vec<PathHolder>>,
+        converted_rx: mpsc::Receiver<()>,
+    ) -> Arc<Self> {
+        let this = Self {
+            path,
+            updated_tx,
+            converted_rx: Arc::new(RwLock::new(converted_rx)),
+            update_forever_handle: Arc::new(RwLock::new(None)),
+            file_modified: Arc::new(RwLock::new(None)),
+            update_lock: Arc::new(Mutex::new(None)),
+            fetch: true,
+            ignored: vec![],
+            updated: Arc::new(RwLock::new(
+                DateTime::from_timestamp(0, 0).unwrap().naive_utc()
+            )),
+            period: Duration::minutes(10),
+            windows: vec![],
+            archive: Archive::default(),
+            limits: Limits::default(),
+            write_unpacked: false,
+            types: vec![]
+        };
+
+        Arc::new(this)
+    }
+
+    fn from_middle(
+        middle: Arc<MiddleIndex>,
+        path: PathBuf,
+        updated_tx: mpsc::Sender<Vec<PathHolder>>,
+        converted_rx: mpsc::Receiver<()>
+    ) -> Arc<Self> {
+        Arc::new(Self::from_middle_shared(
+            middle,
+            path,
+            updated_tx,
+            Arc::new(RwLock::new(converted_rx)),
+            Arc::new(Mutex::new(None))
+        ))
+    }
+
+    fn from_middle_shared(
+        middle: Arc<MiddleIndex>,
+        path: PathBuf,
+        updated_tx: mpsc::Sender<Vec<PathHolder>>,
+        converted_rx: Arc<RwLock<mpsc::Receiver<()>>>,
+        update_lock: Arc<Mutex<Option<Vec<String>>>>
+    ) -> Self {
+        let types = {
+            let mut types = vec![];
+            for sc in middle.types.iter() {
+                let primary = Schedule::from_middle(
+                    Arc::new(sc.clone()),
+                    path.parent().map(
+                        |path| path.to_path_buf()
+                    ).unwrap_or(path.clone()),
+                    middle.archive.clone(),
+                    middle.limits.clone(),
+                    middle.write_unpacked,
+                    middle.updated
+                );
+                types.push(primary);
+            }
+            types
+        };
+
+        Self {
+            path,
+            updated_tx,
+            converted_rx,
+            update_forever_handle: Arc::new(RwLock::new(None)),
+            file_modified: Arc::new(RwLock::new(None)),
+            update_lock,
+            fetch: middle.fetch,
+            ignored: middle.ignored.clone(),
+            updated: Arc::new(RwLock::new(middle.updated)),
+            period: Duration::from_std(middle.period).unwrap(),
+            windows: middle.windows.clone(),
+            archive: middle.archive.clone(),
+            limits: middle.limits.clone(),
+            write_unpacked: middle.write_unpacked,
+            types
+        }
+    }
+
+    pub async fn load_or_init(
+        path: PathBuf,
+        updated_tx: mpsc::Sender<Vec<PathHolder>>,
+        converted_rx: mpsc::Receiver<()>
+    ) -> SyncResult<Arc<Index>> {
+        let this;
+
+        if !path.exists() {
+            this = Self::default(path, updated_tx, converted_rx);
+            this.clone().save().await?;
+        } else {
+            this = Self::load(path, updated_tx, converted_rx).await?;
+        }
+
+        *this.file_modified.write().await = this.read_file_modified().await;
+
+        Ok(this)
+    }
+
+    pub async fn load(
+        path: PathBuf,
+        updated_tx: mpsc::Sender<Vec<PathHolder>>,
+        converted_rx: mpsc::Receiver<()>
+    ) -> SyncResult<Arc<Index>> {
+        let middle = MiddleIndex::load(path.clone()).await?;
+        middle.validate()?;
+        let primary = Self::from_middle(middle, path, updated_tx, converted_rx);
+
+        Ok(primary)
+    }
+
+    /// # Read `index.json` again
+    /// The new index shares channels and the update lock
+    /// with this one, runtime state of schedules
+    /// with the same name and source is carried over.
+    /// The caller must hold the update lock.
+    async fn reloaded(&self) -> SyncResult<Arc<Index>> {
+        let file_modified = self.read_file_modified().await;
+        let middle = MiddleIndex::load(self.path.clone()).await?;
+        middle.validate()?;
+
+        let this = Self::from_middle_shared(
+            middle,
+            self.path.clone(),
+            self.updated_tx.clone(),
+            self.converted_rx.clone(),
+            self.update_lock.clone()
+        );
+
+        *this.updated.write().await = *self.updated.read().await;
+        *this.file_modified.write().await = file_modified;
+
+        for schedule in this.types.iter() {
+            let is_same = self.schedule_by_name(&schedule.name).filter(
+                |old| old.source == schedule.source && old.export == schedule.export
+            );
+
+            if let Some(old) = is_same {
+                schedule.carry_state(&old).await;
+            } else {
+                debug!("{} is new in the index, will update it right away", schedule.name);
+                *schedule.updated.write().await = DateTime::from_timestamp(0, 0).unwrap().naive_utc();
+            }
+        }
+
+        Ok(Arc::new(this))
+    }
+
+    /// # Replace this index with a freshly read one
+    /// Waits for a running update to finish,
+    /// then restarts `update_forever` with the new settings.
+    /// If `index.json` is invalid, this index keeps running.
+    pub async fn reload(self: Arc<Self>) -> SyncResult<Arc<Index>> {
+        let update_lock_ref = self.update_lock.clone();
+        let mut update_lock = update_lock_ref.lock().await;
+        *update_lock = Some(vec![]);
+
+        let new = self.reloaded().await?;
+
+        self.clone().abort_update_forever().await;
+        new.clone().update_forever().await;
+
+        debug!("index reloaded");
+
+        Ok(new)
+    }
+
+    pub async fn groups(&self) -> Vec<Arc<Schedule>> {
+        self.types.iter().filter(|sc| sc.kind == Kind::Groups).cloned().collect::<Vec<Arc<Schedule>>>()
+    }
+
+    pub async fn teachers(&self) -> Vec<Arc<Schedule>> {
+        self.types.iter().filter(|sc| sc.kind == Kind::Teachers).cloned().collect::<Vec<Arc<Schedule>>>()
+    }
+
+    async fn read_file_modified(&self) -> Option<SystemTime> {
+        tokio::fs::metadata(&self.path).await.ok()?.modified().ok()
+    }
+
+    /// # Save in background
+    /// Skipped if `index.json` was edited since we last
+    /// read or wrote it, so the edit isn't lost before a reload.
+    pub async fn poll_save(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut file_modified = self.file_modified.write().await;
+
+            if self.read_file_modified().await != *file_modified {
+                warn!(
+                    "{:?} was edited, reload to apply the changes, \
+                    update state is not saved until then",
+                    self.path
+                );
+                return;
+            }
+
+            if let Err(error) = self.save().await {
+                warn!("error poll saving: {:?}", error);
+            }
+
+            *file_modified = self.read_file_modified().await;
+        });
+    }
+
+    pub fn retry_period(&self) -> Duration {
+        Duration::minutes(1)
+    }
+
+    /// # Policy of schedules that don't have their own
+    pub fn policy(&self) -> Policy {
+        Policy {
+            period: self.period.to_std().unwrap(),
+            windows: self.windows.clone()
+        }
+    }
+
+    /// # Period that is in effect right now
+    pub fn update_period(&self) -> Duration {
+        self.policy().period_now()
+    }
+
+    fn schedule_policy(&self, schedule: &Schedule) -> Policy {
+        schedule.policy.clone().unwrap_or_else(|| self.policy())
+    }
+
+    /// # Schedules that are updated periodically
+    fn updated_types(&self) -> impl Iterator<Item = &Arc<Schedule>> {
+        self.types.iter().filter(|sc| !self.ignored.contains(&sc.name))
+    }
+
+    async fn schedule_next_update(&self, schedule: &Schedule) -> NaiveDateTime {
+        self.schedule_policy(schedule).next_after(*schedule.updated.read().await)
+    }
+
+    /// # The earliest next update of all schedules
+    pub async fn next_update(&self) -> NaiveDateTime {
+        let mut next = None;
+
+        for schedule in self.updated_types() {
+            let schedule_next = self.schedule_next_update(schedule).await;
+            next = Some(next.map_or(schedule_next, |next: NaiveDateTime| next.min(schedule_next)));
+        }
+
+        match next {
+            Some(next) => next,
+            None => self.policy().next_after(*self.updated.read().await)
+        }
+    }
+
+    /// # Names of schedules whose update time has come
+    pub async fn due(&self) -> Vec<String> {
+        let now = Utc::now().naive_utc();
+        let mut due = vec![];
+
+        for schedule in self.updated_types() {
+            if self.schedule_next_update(schedule).await <= now {
+                due.push(schedule.name.clone());
+            }
+        }
+
+        due
+    }
+
+    pub async fn until_next_update(&self) -> Duration {
+        self.next_update().await - Utc::now().naive_utc()
+    }
+
+    pub async fn needs_update(&self) -> bool {
+        self.until_next_update().await < Duration::zero()
+    }
+
+    pub async fn updated_now(&self) {
+        *self.updated.write().await = Utc::now().naive_utc()
+    }
+
+    pub fn schedule_by_name(&self, name: &str) -> Option<Arc<Schedule>> {
+        self.types.iter().find(|sc| sc.name == name).cloned()
+    }
+
+    /// # Fetch, unpack and convert the schedules
+    /// If `only` is set, just these schedules are fetched,
+    /// others are converted from what they had before.
+    pub async fn update_all(self: Arc<Self>, only: Option<Vec<String>>) -> UpdateFinishType {
+        let mut paths = vec![];
+        let update_lock_ref = self.update_lock.clone();
+
+        if update_lock_ref.try_lock().is_err() {
+            debug!(
+                "someone tried to update while the other update is still running, \
+                will return after the main one finishes"
+            );
+
+            // wait for lock to be released
+            let update_lock = update_lock_ref.lock().await;
+
+            debug!("other update finished, returning");
+
+            let is_covered = match (only.as_ref(), update_lock.as_ref()) {
+                (_, None) => true,
+                (Some(names), Some(covered)) => names.iter().all(|name| covered.contains(name)),
+                (None, Some(_)) => false
+            };
+
+            // return when released
+            return UpdateFinishType::LockRelease { is_covered }
+        }
+
+        let update_lock_ref = self.update_lock.clone();
+
+        // lock other threads from updating
+        let mut update_lock = update_lock_ref.lock().await;
+        *update_lock = only.clone();
+
+        if let Some(names) = only.as_ref() {
+            debug!("updating {:?} in Index", names);
+        } else {
+            debug!("updating all schedules in Index");
+        }
+
+        self.updated_now().await;
+
+        let mut handles = vec![];
+
+        // even if nothing is fetched, these schedules
+        // are done until their next period
+        for schedule in self.updated_types() {
+            let is_skipped = only.as_ref().is_some_and(|names| !names.contains(&schedule.name));
+
+            if !is_skipped {
+                *schedule.updated.write().await = *self.updated.read().await;
+            }
+        }
+
+        if self.fetch {
+            for schedule in self.updated_types() {
+                let schedule = schedule.clone();
+                let is_skipped = only.as_ref().is_some_and(|names| !names.contains(&schedule.name));
+
+                let handle = tokio::spawn(async move {
+                    if is_skipped {
+                        return PathHolder {
+                            files: schedule.unpacked().await,
+                            name: schedule.name.clone(),
+                            kind: schedule.kind,
+                            unchanged: true,
+                            failed: false
+                        }
+                    }
+                    schedule.update_with_backoff().await
+                });
+                handles.push(handle);
+            }
+    
+            for handle in handles {
+                let path_index = handle.await.unwrap();
+                paths.push(path_index);
+            }
+
+            debug!("fetched and unpacked all schedules");
+        } else {
+            debug!("fetching is disabled, update bypassed");
+        }
+
+        self.clone().poll_save().await;
+        
+        self.clone().signal_updated(paths).await;
+        self.clone().await_converted().await;
+
+        UpdateFinishType::Complete
+    }
+
+    /// # Archived downloads of every schedule, oldest first
+    pub async fn archived(&self) -> Vec<(NaiveDateTime, Arc<Schedule>, PathBuf)> {
+        let mut archived = vec![];
+
+        for schedule in self.types.iter() {
+            if self.ignored.contains(&schedule.name) {
+                continue;
+            }
+
+            for (time, path) in schedule.archived().await {
+                archived.push((time, schedule.clone(), path));
+            }
+        }
+
+        archived.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
+        archived
+    }
+
+    /// # Feed an archived download through the pipeline
+    /// `replayed` are the schedules that take part in conversion,
+    /// only `schedule` is parsed again.
+    pub async fn replay(
+        self: Arc<Self>,
+        schedule: &Arc<Schedule>,
+        path: &PathBuf,
+        replayed: &[Arc<Schedule>]
+    ) -> Result<(), error::UpdateError> {
+        let update_lock_ref = self.update_lock.clone();
+        let mut update_lock = update_lock_ref.lock().await;
+        *update_lock = Some(vec![schedule.name.clone()]);
+
+        debug!("replaying {:?} of {}", path, schedule.name);
+
+        let bytes = tokio::fs::read(path).await.map_err(error::FetchError::from)?;
+        schedule.clone().extract(Content::Archive(bytes.into())).await?;
+
+        let mut paths = vec![];
+
+        for replayed_schedule in replayed {
+            paths.push(PathHolder {
+                files: replayed_schedule.unpacked().await,
+                name: replayed_schedule.name.clone(),
+                kind: replayed_schedule.kind,
+                unchanged: replayed_schedule.name != schedule.name,
+                failed: false
+            });
+        }
+
+        self.clone().signal_updated(paths).await;
+        self.clone().await_converted().await;
+
+        Ok(())
+    }
+
+    async fn signal_updated(self: Arc<Self>, paths: Vec<PathHolder>) {
+        self.updated_tx.send(paths).await.unwrap();
+        debug!("updated signal sent");
+    }
+
+    async fn await_converted(self: Arc<Self>) {
+        self.converted_rx.write().await.recv().await.unwrap();
+        debug!("converted signal received");
+    }
+
+    pub async fn update_forever(self: Arc<Self>) {
+        let self_ref = self.clone();
+
+        *self_ref.update_forever_handle.write().await = Some(tokio::spawn(async move {
+            loop {
+                let next = self.clone().next_update().await;
+                let mut until = self.clone().until_next_update().await;
+                let zero_dur = Duration::zero();
+    
+                if until < zero_dur {
+                    until = zero_dur;
+                }
+
+                debug!("next fetch: {} (in {} secs)", next, until.num_seconds());
+                tokio::time::sleep(until.to_std().unwrap()).await;
+
+                // someone could have triggered an update while sleeping
+                let due = self.due().await;
+                if due.is_empty() {
+                    // with nothing updated periodically the next
+                    // update never moves, wait a period instead of spinning
+                    if self.updated_types().next().is_none() {
+                        tokio::time::sleep(self.update_period().to_std().unwrap()).await;
+                    }
+                    continue;
+                }
+
+                let is_all_due = self.updated_types().all(|sc| due.contains(&sc.name));
+                self.clone().update_all(if is_all_due { None } else { Some(due) }).await;
+            }
+        }));
+    }
+
+    pub async fn abort_update_forever(self: Arc<Self>) {
+        if let Some(handle) = self.update_forever_handle.read().await.as_ref() {
+            handle.abort()
+        };
+        debug!("aborted update forever");
+    }
+}
+impl json::ToMiddle<MiddleIndex> for Index {
+    async fn to_middle(&self) -> MiddleIndex {
+        let types = {
+            let mut types = vec![];
+            for sc in self.types.iter() {
+                types.push(Schedule::to_middle(sc).await)
+            }
+            types
+        };
+
+        MiddleIndex {
+            path: self.path.clone(),
+            fetch: self.fetch,
+            ignored: self.ignored.clone(),
+            updated: *self.updated.read().await,
+            period: self.period.clone().to_std().unwrap(),
+            windows: self.windows.clone(),
+            archive: self.archive.clone(),
+            limits: self.limits.clone(),
+            write_unpacked: self.write_unpacked,
+            types
+        }
+    }
+}
+impl json::Saving<MiddleIndex> for Index {}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct MiddleIndex {
+    #[serde(skip)]
+    path: PathBuf,
+
+    pub fetch: bool,
+    pub updated: NaiveDateTime,
+    pub period: std::time::Duration,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<policy::Window>,
+    pub ignored: Vec<String>,
+    #[serde(default)]
+    pub archive: Archive,
+    #[serde(default)]
+    pub limits: Limits,
+    #[serde(default)]
+    pub write_unpacked: bool,
+    pub types: Vec<MiddleSchedule>
+}
+impl json::Path for MiddleIndex {
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+}
+impl json::DirectSaving for MiddleIndex {}
+impl json::DirectLoading for MiddleIndex {}
+impl MiddleIndex {
+    pub fn validate(&self) -> Result<(), error::IndexError> {
+        let policy = Policy {
+            period: self.period,
+            windows: self.windows.clone()
+        };
+        if policy.has_zero_period() {
+            return Err(error::IndexError::ZeroPeriod)
+        }
+
+        if self.types.iter().all(|schedule| self.ignored.contains(&schedule.name)) {
+            return Err(error::IndexError::NothingUpdated)
+        }
+
+        for (idx, schedule) in self.types.iter().enumerate() {
+            let is_duplicate = self.types
+                .iter()
+                .skip(idx + 1)
+                .any(|other| other.name == schedule.name);
+            if is_duplicate {
+                return Err(error::IndexError::DuplicateName(schedule.name.clone()))
+            }
+
+            if schedule.policy.as_ref().is_some_and(|policy| policy.has_zero_period()) {
+                return Err(error::IndexError::ZeroSchedulePeriod(schedule.name.clone()))
+            }
+
+            if schedule.source.is_none() {
+                if let Some(path) = Source::guessed_path(&schedule.url) {
+                    if !path.exists() {
+                        return Err(error::IndexError::MissingPath(schedule.name.clone(), path))
+                    }
+                }
+            }
+
+            if let Err(error) = schedule.http.client(schedule.fetch_timeout) {
+                return Err(error::IndexError::Http(schedule.name.clone(), error))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    root: PathBuf,
+    reqwest: reqwest::Client,
+
+    pub kind: Kind,
+    pub name: String,
+    pub url: String,
+    pub source: Source,
+    pub export: Export,
+    pub fetch_timeout: std::time::Duration,
+    pub retry_period: std::time::Duration,
+    /// # `ETag` header from the last successful fetch
+    pub etag: Arc<RwLock<Option<String>>>,
+    /// # `Last-Modified` header from the last successful fetch
+    pub last_modified: Arc<RwLock<Option<String>>>,
+    /// # SHA-256 of the last unpacked archive
+    pub hash: Arc<RwLock<Option<String>>>,
+    pub backoff: Backoff,
+    pub health: Arc<RwLock<Health>>,
+    pub archive: Archive,
+    pub limits: Limits,
+    pub write_unpacked: bool,
+    /// # Files extracted by the last successful update
+    files: Arc<RwLock<Vec<File>>>,
+    /// # Overrides the index policy
+    pub policy: Option<Policy>,
+    /// # When this schedule was last updated
+    pub updated: Arc<RwLock<NaiveDateTime>>,
+    pub http: Http,
+}
+
+impl json::ToMiddle<MiddleSchedule> for Schedule {
+    async fn to_middle(&self) -> MiddleSchedule {
+        MiddleSchedule {
+            root: self.root.clone(),
+            kind: self.kind,
+            name: self.name.clone(),
+            url: self.url.clone(),
+            source: if Source::from_url(&self.url) == self.source {
+                None
+            } else {
+                Some(self.source.clone())
+            },
+            export: self.export,
+            fetch_timeout: self.fetch_timeout,
+            retry_period: self.retry_period,
+            etag: self.etag.read().await.clone(),
+            last_modified: self.last_modified.read().await.clone(),
+            hash: self.hash.read().await.clone(),
+            backoff: self.backoff.clone(),
+            health: self.health.read().await.clone(),
+            policy: self.policy.clone(),
+            updated: Some(*self.updated.read().await),
+            http: self.http.clone(),
+        }
+    }
+}
+impl Schedule {
+    fn from_middle(
+        middle: Arc<MiddleSchedule>,
+        root: PathBuf,
+        archive: Archive,
+        limits: Limits,
+        write_unpacked: bool,
+        updated: NaiveDateTime
+    ) -> Arc<Self> {
+        // checked in `MiddleIndex::validate`
+        let reqwest = middle.http.client(middle.fetch_timeout).unwrap();
+        
+        let this = Schedule {
+            root,
+            reqwest,
+            kind: middle.kind,
+            name: middle.name.clone(),
+            url: middle.url.clone(),
+            source: middle.source.clone().unwrap_or_else(
+                || Source::from_url(&middle.url)
+            ),
+            export: middle.export,
+            fetch_timeout: middle.fetch_timeout,
+            retry_period: middle.retry_period,
+            etag: Arc::new(RwLock::new(middle.etag.clone())),
+            last_modified: Arc::new(RwLock::new(middle.last_modified.clone())),
+            hash: Arc::new(RwLock::new(middle.hash.clone())),
+            backoff: middle.backoff.clone(),
+            health: Arc::new(RwLock::new(middle.health.clone())),
+            archive,
+            limits,
+            write_unpacked,
+            files: Arc::new(RwLock::new(vec![])),
+            policy: middle.policy.clone(),
+            updated: Arc::new(RwLock::new(middle.updated.unwrap_or(updated))),
+            http: middle.http.clone(),
+        };
+
+        Arc::new(this)
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.root.join(self.name.clone())
+    }
+
+    pub fn archive_dir(&self) -> PathBuf {
+        self.root.join(Archive::DIR).join(self.name.clone())
+    }
+
+    /// # Archived downloads, oldest first
+    pub async fn archived(&self) -> Vec<(NaiveDateTime, PathBuf)> {
+        Archive::list(&self.archive_dir(), Some(self.export.archive_extension())).await
+    }
+
+    /// # Take over runtime state from a previous instance
+    async fn carry_state(&self, old: &Schedule) {
+        *self.etag.write().await = old.etag.read().await.clone();
+        *self.last_modified.write().await = old.last_modified.read().await.clone();
+        *self.hash.write().await = old.hash.read().await.clone();
+        *self.health.write().await = old.health.read().await.clone();
+        *self.updated.write().await = *old.updated.read().await;
+        *self.files.write().await = old.files.read().await.clone();
+    }
+
+    async fn set_validators(&self, etag: Option<String>, last_modified: Option<String>) {
+        *self.etag.write().await = etag;
+        *self.last_modified.write().await = last_modified;
+    }
+
+    /// # Files to parse left from the last update
+    /// After a restart they are restored
+    /// from the newest archived download.
+    pub async fn unpacked(&self) -> Vec<File> {
+        if self.files.read().await.is_empty() {
+            self.restore().await;
+        }
+
+        self.files.read().await.clone()
+    }
+
+    /// # Extract the newest archive again
+    /// if it's the one that was unpacked last time
+    async fn restore(&self) {
+        let Some(hash) = self.hash.read().await.clone() else { return };
+        let Some((_, path)) = self.archived().await.pop() else { return };
+        let Ok(bytes) = tokio::fs::read(&path).await else { return };
+
+        let content = Content::Archive(bytes.into());
+        if content.hash() != hash {
+            return
+        }
+
+        match self.extract_files(content).await {
+            Ok(files) => {
+                debug!("restored {} from {:?}", self.name, path);
+                *self.files.write().await = files;
+            },
+            Err(error) => warn!("failed to restore {} from {:?}: {:?}", self.name, path, error)
+        }
+    }
+
+    /// # Fetch the schedule from its source
+    pub async fn fetch(&self) -> Result<Fetched, error::FetchError> {
+        let content = match &self.source {
+            Source::Http { url } => return self.fetch_http(url).await,
+            Source::Zip { path } => Content::Archive(Source::read_zip(path).await?),
+            Source::Directory { path } => Content::Files(Source::read_directory(
+                path,
+                &self.limits,
+                self.export.extension()
+            ).await?)
+        };
+
+        Ok(Fetched::Modified { content, etag: None, last_modified: None })
+    }
+
+    /// # Download the archive
+    /// If the last unpacked archive is still on disk,
+    /// a conditional request is sent using the validators
+    /// from the previous response.
+    async fn fetch_http(&self, url: &str) -> Result<Fetched, error::FetchError> {
+        let mut request = self.http.authorize(self.reqwest.get(url));
+
+        if self.hash.read().await.is_some() && !self.unpacked().await.is_empty() {
+            if let Some(etag) = self.etag.read().await.as_ref() {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = self.last_modified.read().await.as_ref() {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let resp = request.send().await?;
+
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified)
+        }
+
+        let header_string = |name| resp.headers()
+            .get(name)
+            .and_then(|value: &header::HeaderValue| value.to_str().ok())
+            .map(|value| value.to_string());
+        let etag = header_string(header::ETAG);
+        let last_modified = header_string(header::LAST_MODIFIED);
+
+        let bytes = resp.bytes().await?;
+
+        Ok(Fetched::Modified {
+            content: Content::Archive(bytes),
+            etag,
+            last_modified
+        })
+    }
+
+    pub async fn fetch_after(&self, after: Duration) -> Result<Fetched, error::FetchError> {
+        tokio::time::sleep(after.to_std().unwrap()).await;
+        self.fetch().await
+    }
+
+    /// # Fetch and extract once
+    /// Returns files to parse and whether
+    /// the content is the same as before.
+    async fn try_update(self: Arc<Self>) -> Result<(Vec<File>, bool), error::UpdateError> {
+        debug!("fetching {} ({})", self.name, self.source);
+
+        let Fetched::Modified {
+            content,
+            etag,
+            last_modified
+        } = self.fetch().await? else {
+            debug!("{} was not modified since the last fetch", self.name);
+            return Ok((self.unpacked().await, true))
+        };
+
+        debug!("fetching {} success", self.name);
+
+        let hash = content.hash();
+
+        if self.hash.read().await.as_ref() == Some(&hash) {
+            let unpacked = self.unpacked().await;
+            if !unpacked.is_empty() {
+                debug!("{} content hash didn't change, skipping unpack", self.name);
+                self.set_validators(etag, last_modified).await;
+                return Ok((unpacked, true))
+            }
+        }
+
+        let archived = match &content {
+            Content::Archive(bytes) => Some(bytes.clone()),
+            Content::Files(_) => None
+        };
+
+        let extracted = self.clone().extract(content).await?;
+
+        *self.hash.write().await = Some(hash);
+        self.set_validators(etag, last_modified).await;
+
+        if let Some(bytes) = archived {
+            let stored = self.archive.store(
+                &self.archive_dir(),
+                &bytes,
+                self.export.archive_extension()
+            ).await;
+            if let Err(error) = stored {
+                warn!("failed to archive {}: {:?}", self.name, error);
+            }
+        }
+
+        Ok((extracted, false))
+    }
+
+    /// # Update, retrying with an exponential backoff
+    /// Gives up after `backoff.max_attempts` tries
+    /// or `backoff.deadline`, whatever comes first.
+    /// In that case the files from the last successful
+    /// update are left to be parsed.
+    pub async fn update_with_backoff(self: Arc<Self>) -> PathHolder {
+        let started = std::time::Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            match self.clone().try_update().await {
+                Ok((files, unchanged)) => {
+                    self.health.write().await.succeeded();
+                    return PathHolder {
+                        files,
+                        name: self.name.clone(),
+                        kind: self.kind,
+                        unchanged,
+                        failed: false
+                    }
+                },
+                Err(error) => {
+                    self.health.write().await.failed(format!("{:?}", error));
+
+                    let delay = self.backoff.delay(self.retry_period, attempt);
+                    let out_of_attempts = attempt >= self.backoff.max_attempts;
+                    let out_of_time = started.elapsed() + delay > self.backoff.deadline;
+
+                    if out_of_attempts || out_of_time {
+                        warn!(
+                            "{} update failed {} times, giving up until the next update: {:?}",
+                            self.name,
+                            attempt,
+                            error
+                        );
+                        return PathHolder {
+                            files: self.unpacked().await,
+                            name: self.name.clone(),
+                            kind: self.kind,
+                            unchanged: true,
+                            failed: true
+                        }
+                    }
+
+                    warn!(
+                        "{} update error, will retry in {:?}: {:?}",
+                        self.name,
+                        delay,
+                        error
+                    );
+
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// # Extract fetched content and keep it for the next updates
+    pub async fn extract(self: Arc<Self>, content: Content) -> Result<Vec<File>, error::UnpackError> {
+        let files = self.extract_files(content).await?;
+
+        if self.write_unpacked {
+            self.write(&files).await?;
+        }
+
+        *self.files.write().await = files.clone();
+
+        Ok(files)
+    }
+
+    /// # Files to parse from fetched content
+    /// Their paths are inside of `dir()`,
+    /// but nothing is written there.
+    async fn extract_files(&self, content: Content) -> Result<Vec<File>, error::UnpackError> {
+        let mut files = match (content, self.export) {
+            (Content::Archive(bytes), Export::Zip) => self.unpack(bytes).await?,
+            (Content::Archive(bytes), Export::Xlsx) => self.workbook(bytes)?,
+            (Content::Files(files), _) => self.relocate(files)
+        };
+
+        let parsed_ext = std::ffi::OsStr::new(self.export.extension());
+        files.retain(|file| file.path.extension() == Some(parsed_ext));
+
+        Ok(files)
+    }
+
+    /// # XLSX export is parsed as a whole
+    /// Only the downloaded size is checked here, parts
+    /// of the workbook are checked when it's parsed.
+    fn workbook(&self, bytes: Bytes) -> Result<Vec<File>, error::UnpackError> {
+        let filename = format!("{}.{}", self.name, self.export.extension());
+        self.limits.check_size(&filename, bytes.len() as u64, 0)?;
+
+        let file = File {
+            path: self.dir().join(fs::path::sanitize(&filename)),
+            bytes
+        };
+
+        Ok(vec![file])
+    }
+
+    /// # Move files from a directory source into `dir()`
+    fn relocate(&self, files: Vec<File>) -> Vec<File> {
+        let dir = self.dir();
+
+        files.into_iter().map(|file| File {
+            path: dir.join(fs::path::sanitize(&file.path.to_string_lossy())),
+            bytes: file.bytes
+        }).collect()
+    }
+
+    async fn recreate_dir(&self) -> Result<(), error::UnpackError> {
+        let dir = self.dir();
+
+        if dir.exists() {
+            debug!("{:?} exists, removing before writing", dir);
+            if let Err(error) = tokio::fs::remove_dir_all(&dir).await {
+                return Err(error::UnpackError::Io(error));
+            }
+        }
+
+        if !dir.exists() {
+            debug!("{:?} doesn't exist, creating to write there", dir);
+            if let Err(error) = tokio::fs::create_dir(&dir).await {
+                return Err(error::UnpackError::Io(error));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Read a ZIP archive into memory
+    /// Only the entries allowed by `limits` are read.
+    pub async fn unpack(&self, bytes: Bytes) -> Result<Vec<File>, error::UnpackError> {
+        let dir = self.dir();
+        let parsed_ext = self.export.extension();
+
+        debug!("unpacking {} archive", self.name);
+
+        let cursor = Cursor::new(bytes);
+        let archive_result = ZipFileReader::with_tokio(cursor).await;
+
+        if let Err(error) = archive_result {
+            return Err(error::UnpackError::Zip(error));
+        }
+
+        let mut archive = archive_result.unwrap();
+        self.limits.check_entries(archive.file().entries().len())?;
+
+        let mut files: Vec<File> = vec![];
+        let mut total: u64 = 0;
+
+        for index in 0..archive.file().entries().len() {
+            let entry = archive.file().entries().get(index).unwrap();
+            let filename = entry.filename().as_str().unwrap_or_default().to_string();
+            let path = dir.join(fs::path::sanitize(&filename));
+
+            if entry.dir().unwrap_or(true) || !self.limits.is_allowed(&path, parsed_ext) {
+                debug!("skipping {:?} entry {:?}", self.name, filename);
+                continue;
+            }
+
+            // the declared size may be a lie, so it's
+            // also checked while reading below
+            self.limits.check_size(&filename, entry.uncompressed_size(), total)?;
+
+            let entry_reader = archive.reader_without_entry(index).await;
+            if let Err(err) = entry_reader {
+                warn!("entry reader error: {:?}", err);
+                continue;
+            }
+            let entry_reader = entry_reader.unwrap().compat();
+
+            // one byte over the budget is enough
+            // to tell that the limit is exceeded
+            let budget = self.limits.entry_budget(total).map_or(u64::MAX, |budget| budget + 1);
+
+            let mut buf = vec![];
+            let copy_result = entry_reader.take(budget).read_to_end(&mut buf).await;
+
+            if let Err(err) = copy_result {
+                warn!("failed to read archive entry: {:?}", err);
+                continue;
+            }
+
+            self.limits.check_size(&filename, buf.len() as u64, total)?;
+            total += buf.len() as u64;
+
+            files.push(File {
+                path,
+                bytes: buf.into()
+            });
+        }
+        debug!("unpacked {} archive ({} bytes)", self.name, total);
+
+        Ok(files)
+    }
+
+    /// # Write extracted files to `dir()`
+    async fn write(&self, files: &[File]) -> Result<(), error::UnpackError> {
+        self.recreate_dir().await?;
+
+        for file in files.iter() {
+            // parent directories may not exist if the archive
+            // does not contain directory entries
+            if let Some(parent) = file.path.parent() {
+                if let Err(err) = tokio::fs::create_dir_all(parent).await {
+                    warn!("failed to create parent directories: {:?}", err);
+                    continue;
+                }
+            }
+
+            if let Err(err) = tokio::fs::write(&file.path, &file.bytes[..]).await {
+                warn!("failed to write file: {:?}", err);
+            }
+        }
+
+        debug!("wrote {} files to {:?}", files.len(), self.dir());
+
+        Ok(())
+    }
+}
+
+/// # Fetched schedule files
+pub enum Content {
+    /// # ZIP archive
+    Archive(Bytes),
+    /// # Already unpacked files,
+    /// paths are relative to the source
+    Files(Vec<File>)
+}
+impl Content {
+    /// # SHA-256 of the content
+    pub fn hash(&self) -> String {
+        let mut hasher = Sha256::new();
+
+        match self {
+            Self::Archive(bytes) => hasher.update(bytes),
+            Self::Files(files) => for file in files {
+                hasher.update(file.path.to_string_lossy().as_bytes());
+                hasher.update((file.bytes.len() as u64).to_le_bytes());
+                hasher.update(&file.bytes);
+            }
+        }
+
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// # Result of a fetch
+pub enum Fetched {
+    /// # New content with its cache validators
+    Modified {
+        content: Content,
+        etag: Option<String>,
+        last_modified: Option<String>
+    },
+    /// # Server replied with `304 Not Modified`
+    NotModified
+}
+
+#[derive(Debug)]
+pub struct PathHolder {
+    pub files: Vec<File>,
+    pub name: String,
+    pub kind: Kind,
+    /// # Content is the same as in the previous update
+    pub unchanged: bool,
+    /// # Update gave up, `files` are left
+    /// from the last successful one
+    pub failed: bool
+}
+
+/// # Stores last converted raw schedules
+#[derive(Debug)]
+pub struct PathContainer {
+    pub list: Arc<RwLock<Vec<PathHolder>>>
+}
+impl PathContainer {
+    pub fn default() -> Arc<Self> {
+        let this = Self {
+            list: Arc::new(RwLock::new(vec![]))
+        };
+        Arc::new(this)
+    }
+
+    /// # Check if all `names` are present
+    pub async fn is_complete(&self, reference: &[String]) -> bool {
+        self.list.read().await.iter().all(|sc| reference.contains(&sc.name))
+    }
+
+    /// # Get `names` that are not present
+    pub async fn missing_names(&self, reference: &[String]) -> Vec<String> {
+        self.list.read().await.iter()
+            .filter(|sc| !reference.contains(&sc.name))
+            .map(|sc| sc.name.clone())
+            .collect()
+    }
+}
+
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MiddleSchedule {
+    #[serde(skip)]
+    root: PathBuf,
+
+    pub kind: Kind,
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    /// # Overrides `url` if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    #[serde(default)]
+    pub export: Export,
+    pub fetch_timeout: std::time::Duration,
+    pub retry_period: std::time::Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub backoff: Backoff,
+    #[serde(default)]
+    pub health: Health,
+    /// # Overrides the index `period` and `windows`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<Policy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<NaiveDateTime>,
+    /// # Headers, auth, proxy, redirects and CA
+    #[serde(default, skip_serializing_if = "Http::is_default")]
+    pub http: Http,
+}
+impl MiddleSchedule {
+    pub fn example() -> Self {
+        Self {
+            root: std::path::PathBuf::new(),
+            kind: crate::data::schedule::raw::Kind::Groups,
+            name: "Containing folder name".to_string(),
+            url: "https://docs.google.com/document/d/13FImWkHpdV_dgDCp7Py36gYPr53C-dYeUvNklkndaPA/export?format=zip".to_string(),
+            source: None,
+            export: Export::Zip,
+            fetch_timeout: std::time::Duration::from_secs(90),
+            retry_period: std::time::Duration::from_secs(2),
+            etag: None,
+            last_modified: None,
+            hash: None,
+            backoff: Backoff::default(),
+            health: Health::default(),
+            policy: None,
+            updated: None,
+            http: Http::default()
+        }
+    }
+    pub fn dir(&self) -> PathBuf {
+        self.root.join(self.kind.to_string())
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::data::json::ToMiddle;
+use super::*;
+
+
+#[tokio::test]
+async fn test_update_forever_without_fetch() {
+    crate::init_test_globals().await;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+
+    let (updated_tx, mut updated_rx) = mpsc::channel(1);
+    let (converted_tx, converted_rx) = mpsc::channel(1);
+
+    let mut index = Index::default(dir.join("index.json"), updated_tx, converted_rx);
+    {
+        let index = Arc::get_mut(&mut index).unwrap();
+        index.fetch = false;
+        index.types = vec![Schedule::from_middle(
+            Arc::new(MiddleSchedule::example()),
+            dir.clone(),
+            Archive::default(),
+            Limits::default(),
+            false,
+            DateTime::from_timestamp(0, 0).unwrap().naive_utc()
+        )];
+    }
+
+    let updates = Arc::new(AtomicUsize::new(0));
+    let updates_ref = updates.clone();
+    let converter = tokio::spawn(async move {
+        while updated_rx.recv().await.is_some() {
+            updates_ref.fetch_add(1, Ordering::SeqCst);
+            converted_tx.send(()).await.unwrap();
+        }
+    });
+
+    index.clone().update_forever().await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    index.clone().abort_update_forever().await;
+    converter.abort();
+
+    // the schedule was due once, then the loop
+    // sleeps until the next period instead of spinning
+    assert_eq!(updates.load(Ordering::SeqCst), 1);
+    assert!(!index.needs_update().await);
+}
+
+#[tokio::test]
+async fn test_validate_nothing_updated() {
+    let (updated_tx, _updated_rx) = mpsc::channel(1);
+    let (_converted_tx, converted_rx) = mpsc::channel(1);
+
+    let index = Index::default(PathBuf::from("index.json"), updated_tx, converted_rx);
+    let mut middle = index.to_middle().await;
+    assert!(matches!(middle.validate(), Err(error::IndexError::NothingUpdated)));
+
+    middle.types.push(MiddleSchedule::example());
+    assert!(middle.validate().is_ok());
+
+    middle.ignored.push(MiddleSchedule::example().name);
+    assert!(matches!(middle.validate(), Err(error::IndexError::NothingUpdated)));
+}
@@ -0,0 +1,83 @@
+use serde_derive::{Serialize, Deserialize};
+use std::path::Path;
+use crate::data::schedule::raw::error::LimitError;
+
+
+/// # Limits for unpacking downloaded archives
+/// Protect the server from malformed or hostile
+/// exports that could exhaust memory or disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// # How many entries an archive may have
+    pub max_entries: Option<usize>,
+    /// # Decompressed size of a single entry in bytes
+    pub max_entry_size: Option<u64>,
+    /// # Decompressed size of all entries in bytes
+    pub max_total_size: Option<u64>,
+    /// # Extensions of entries that are extracted,
+    /// only the parsed one if empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>
+}
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_entries: Some(1000),
+            max_entry_size: Some(64 * 1024 * 1024),
+            max_total_size: Some(256 * 1024 * 1024),
+            extensions: vec![]
+        }
+    }
+}
+impl Limits {
+    pub fn check_entries(&self, count: usize) -> Result<(), LimitError> {
+        match self.max_entries {
+            Some(max) if count > max => Err(LimitError::Entries { count, max }),
+            _ => Ok(())
+        }
+    }
+
+    /// # Should this entry be extracted at all
+    pub fn is_allowed(&self, path: &Path, parsed_extension: &str) -> bool {
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            return false
+        };
+
+        if self.extensions.is_empty() {
+            extension.eq_ignore_ascii_case(parsed_extension)
+        } else {
+            self.extensions.iter().any(|allowed| extension.eq_ignore_ascii_case(allowed))
+        }
+    }
+
+    /// # How many bytes the next entry may take
+    /// if `total` bytes were already extracted
+    pub fn entry_budget(&self, total: u64) -> Option<u64> {
+        let left = self.max_total_size.map(|max| max.saturating_sub(total));
+
+        match (self.max_entry_size, left) {
+            (Some(entry), Some(left)) => Some(entry.min(left)),
+            (entry, left) => entry.or(left)
+        }
+    }
+
+    /// # Check the size of an entry
+    /// `size` is either declared in the archive
+    /// or actually read, `total` doesn't include it.
+    pub fn check_size(&self, name: &str, size: u64, total: u64) -> Result<(), LimitError> {
+        if let Some(max) = self.max_entry_size {
+            if size > max {
+                return Err(LimitError::EntrySize { name: name.to_string(), max })
+            }
+        }
+
+        if let Some(max) = self.max_total_size {
+            if total.saturating_add(size) > max {
+                return Err(LimitError::TotalSize { max })
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,22 @@
 pub mod index;
 pub mod table;
 pub mod error;
+pub mod source;
+pub mod backoff;
+pub mod health;
+pub mod archive;
+pub mod policy;
+pub mod limits;
+pub mod http;
 
 pub use index::Index;
+pub use source::Source;
+pub use backoff::Backoff;
+pub use health::Health;
+pub use archive::Archive;
+pub use policy::Policy;
+pub use limits::Limits;
+pub use http::Http;
 
 use serde_derive::{Serialize, Deserialize};
 use strum_macros::{EnumString, Display};
@@ -37,6 +51,43 @@ impl Kind {
     }
 }
 
+/// # Format of the schedule export
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Copy,
+    Default
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Export {
+    /// # ZIP archive with an HTML file per sheet
+    #[default]
+    Zip,
+    /// # Single XLSX workbook
+    Xlsx
+}
+impl Export {
+    /// # Extension of files that are parsed
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Zip => "html",
+            Self::Xlsx => crate::parse::sheet::XLSX_EXTENSION
+        }
+    }
+
+    /// # Extension of the downloaded file
+    pub fn archive_extension(&self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Xlsx => crate::parse::sheet::XLSX_EXTENSION
+        }
+    }
+}
+
 /// # Format of a lesson
 #[derive(
     Serialize, 
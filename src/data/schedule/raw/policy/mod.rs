@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use serde_derive::{Serialize, Deserialize};
+use crate::data::Weekday;
+
+
+/// # Time of the day with its own update period
+/// If `end` is before `start`, the window
+/// goes over midnight, e.g. from 22:00 to 06:00.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Window {
+    /// # Days when the window starts,
+    /// every day if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekdays: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub period: std::time::Duration
+}
+impl Window {
+    fn is_on(&self, datetime: NaiveDateTime) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(
+            &Weekday::from_chrono(&chrono::Datelike::weekday(&datetime.date()))
+        )
+    }
+
+    pub fn contains(&self, datetime: NaiveDateTime) -> bool {
+        let time = datetime.time();
+
+        if self.start <= self.end {
+            self.is_on(datetime) && self.start <= time && time < self.end
+        } else {
+            (self.start <= time && self.is_on(datetime))
+            || (time < self.end && self.is_on(datetime - Duration::days(1)))
+        }
+    }
+
+    /// # Starts and ends of this window between `from` and `to`
+    fn boundaries(&self, from: NaiveDateTime, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut boundaries = vec![];
+        let mut date = from.date() - Duration::days(1);
+
+        while date <= to.date() {
+            let start = date.and_time(self.start);
+
+            if self.is_on(start) {
+                let end = if self.start <= self.end {
+                    date.and_time(self.end)
+                } else {
+                    (date + Duration::days(1)).and_time(self.end)
+                };
+
+                boundaries.extend([start, end].into_iter().filter(
+                    |boundary| from < *boundary && *boundary < to
+                ));
+            }
+
+            date += Duration::days(1);
+        }
+
+        boundaries
+    }
+}
+
+/// # When to update
+/// Inside of a window its period is used,
+/// if windows overlap, the first one wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Policy {
+    /// # Period outside of windows
+    pub period: std::time::Duration,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<Window>
+}
+impl Policy {
+    /// # Update period at this local time
+    pub fn period_at(&self, local: NaiveDateTime) -> Duration {
+        let period = self.windows
+            .iter()
+            .find(|window| window.contains(local))
+            .map(|window| window.period)
+            .unwrap_or(self.period);
+
+        Duration::from_std(period).unwrap_or(Duration::max_value())
+    }
+
+    /// # Next update after the one at `last` (UTC)
+    /// If a window with a shorter period starts earlier,
+    /// the update is moved to its start.
+    pub fn next_after(&self, last: NaiveDateTime) -> NaiveDateTime {
+        Self::to_utc(self.next_after_local(Self::to_local(last)))
+    }
+
+    /// # Same as `next_after`, but in local time
+    pub fn next_after_local(&self, local: NaiveDateTime) -> NaiveDateTime {
+        let mut next = local.checked_add_signed(self.period_at(local))
+            .unwrap_or(NaiveDateTime::MAX);
+
+        let mut boundaries = self.windows
+            .iter()
+            .flat_map(|window| window.boundaries(local, next))
+            .collect::<Vec<NaiveDateTime>>();
+        boundaries.sort();
+
+        for boundary in boundaries {
+            let since_boundary = local.checked_add_signed(self.period_at(boundary))
+                .unwrap_or(NaiveDateTime::MAX)
+                .max(boundary);
+            next = next.min(since_boundary);
+        }
+
+        next
+    }
+
+    /// # A zero period would update in a busy loop
+    pub fn has_zero_period(&self) -> bool {
+        self.period.is_zero() || self.windows.iter().any(|window| window.period.is_zero())
+    }
+
+    pub fn period_now(&self) -> Duration {
+        self.period_at(crate::options().settings().now())
+    }
+
+    fn to_local(utc: NaiveDateTime) -> NaiveDateTime {
+        crate::options().settings().to_local(utc)
+    }
+
+    fn to_utc(local: NaiveDateTime) -> NaiveDateTime {
+        crate::options().settings().to_utc(local)
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::NaiveDate;
+use super::*;
+
+
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    // 2024-09-02 is a Monday
+    NaiveDate::from_ymd_opt(2024, 9, day)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// Every 2 minutes 14:00-22:00 on weekdays, hourly otherwise
+fn policy() -> Policy {
+    Policy {
+        period: std::time::Duration::from_secs(60 * 60),
+        windows: vec![
+            Window {
+                weekdays: vec![
+                    Weekday::Monday,
+                    Weekday::Tuesday,
+                    Weekday::Wednesday,
+                    Weekday::Thursday,
+                    Weekday::Friday
+                ],
+                start: time(14, 0),
+                end: time(22, 0),
+                period: std::time::Duration::from_secs(2 * 60)
+            }
+        ]
+    }
+}
+
+#[test]
+fn test_window_over_midnight() {
+    let window = Window {
+        weekdays: vec![Weekday::Friday],
+        start: time(22, 0),
+        end: time(6, 0),
+        period: std::time::Duration::from_secs(0)
+    };
+
+    // friday evening and the night after it
+    assert!(window.contains(at(6, 23, 0)));
+    assert!(window.contains(at(7, 5, 59)));
+    // friday morning belongs to thursday night
+    assert!(!window.contains(at(6, 5, 0)));
+    assert!(!window.contains(at(7, 6, 0)));
+}
+
+#[test]
+fn test_period_at() {
+    let policy = policy();
+
+    assert_eq!(policy.period_at(at(2, 15, 0)), Duration::minutes(2));
+    assert_eq!(policy.period_at(at(2, 22, 0)), Duration::hours(1));
+    // saturday
+    assert_eq!(policy.period_at(at(7, 15, 0)), Duration::hours(1));
+}
+
+#[test]
+fn test_next_after() {
+    let policy = policy();
+
+    // inside of the window
+    assert_eq!(policy.next_after_local(at(2, 15, 0)), at(2, 15, 2));
+    // the window starts before the hour passes
+    assert_eq!(policy.next_after_local(at(2, 13, 30)), at(2, 14, 0));
+    // the window ends, the last short period is kept
+    assert_eq!(policy.next_after_local(at(2, 21, 59)), at(2, 22, 1));
+    // no window on saturday
+    assert_eq!(policy.next_after_local(at(7, 13, 30)), at(7, 14, 30));
+}
+
+#[test]
+fn test_has_zero_period() {
+    let mut policy = policy();
+    assert!(!policy.has_zero_period());
+
+    policy.windows[0].period = std::time::Duration::ZERO;
+    assert!(policy.has_zero_period());
+}
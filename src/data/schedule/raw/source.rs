@@ -0,0 +1,126 @@
+use log::debug;
+use serde_derive::{Serialize, Deserialize};
+use actix_web::web::Bytes;
+use tokio::io::AsyncReadExt;
+use std::path::{Path, PathBuf};
+use crate::data::schedule::{
+    raw::{Limits, error::FetchError},
+    File
+};
+
+
+const FILE_SCHEME: &str = "file://";
+
+
+/// # Where a schedule is taken from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Source {
+    /// # ZIP archive downloaded over HTTP(S)
+    Http { url: String },
+    /// # ZIP archive on the local disk
+    Zip { path: PathBuf },
+    /// # Directory with HTML files on the local disk
+    Directory { path: PathBuf }
+}
+impl Source {
+    /// # Guess the source from a URL
+    /// - `file:///srv/export.zip` -> `Zip`
+    /// - `file:///srv/export` (existing directory) -> `Directory`
+    /// - anything else -> `Http`
+    ///
+    /// A `file://` path that doesn't exist yet can't be
+    /// told apart, so `MiddleIndex::validate` rejects it.
+    pub fn from_url(url: &str) -> Self {
+        let Some(path) = Self::guessed_path(url) else {
+            return Self::Http { url: url.to_string() }
+        };
+
+        if path.is_dir() {
+            Self::Directory { path }
+        } else {
+            Self::Zip { path }
+        }
+    }
+
+    pub async fn read_zip(path: &Path) -> std::io::Result<Bytes> {
+        let bytes = tokio::fs::read(path).await?;
+        Ok(bytes.into())
+    }
+
+    /// # Local path that `from_url` had to guess the kind for
+    pub fn guessed_path(url: &str) -> Option<PathBuf> {
+        url.strip_prefix(FILE_SCHEME).map(PathBuf::from)
+    }
+
+    /// # Read every file in a directory recursively
+    /// Paths of the returned files are relative to `path`.
+    /// Only the files allowed by `limits` are read,
+    /// the same way as entries of a ZIP archive.
+    pub async fn read_directory(
+        path: &Path,
+        limits: &Limits,
+        parsed_extension: &str
+    ) -> Result<Vec<File>, FetchError> {
+        let mut files = vec![];
+        let mut dirs = vec![path.to_path_buf()];
+        let mut total: u64 = 0;
+
+        while let Some(dir) = dirs.pop() {
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let entry_path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    dirs.push(entry_path);
+                    continue;
+                }
+
+                let relative = entry_path
+                    .strip_prefix(path)
+                    .map(|relative| relative.to_path_buf())
+                    .unwrap_or(entry_path.clone());
+
+                if !limits.is_allowed(&relative, parsed_extension) {
+                    debug!("skipping {:?}", entry_path);
+                    continue;
+                }
+
+                limits.check_entries(files.len() + 1)?;
+
+                let name = relative.to_string_lossy().to_string();
+                let size = entry.metadata().await?.len();
+                limits.check_size(&name, size, total)?;
+
+                // the file may grow after the check above
+                let budget = limits.entry_budget(total).map_or(u64::MAX, |budget| budget + 1);
+
+                let mut bytes = vec![];
+                tokio::fs::File::open(&entry_path).await?
+                    .take(budget)
+                    .read_to_end(&mut bytes)
+                    .await?;
+
+                limits.check_size(&name, bytes.len() as u64, total)?;
+                total += bytes.len() as u64;
+
+                files.push(File {
+                    path: relative,
+                    bytes: bytes.into()
+                });
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+}
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Http { url } => write!(f, "{}", url),
+            Self::Zip { path } => write!(f, "{}{}", FILE_SCHEME, path.display()),
+            Self::Directory { path } => write!(f, "{}{}", FILE_SCHEME, path.display())
+        }
+    }
+}
@@ -34,7 +34,11 @@ pub struct Cell {
     pub colspan: usize,
     pub rowspan: usize,
     pub text: String,
-    pub color: palette::Srgb
+    /// # Non-empty lines of `text` as they were in the cell
+    pub lines: Vec<String>,
+    pub color: palette::Srgb,
+    /// # Whole cell is struck through
+    pub strikethrough: bool
 }
 impl XCord for Cell {
     fn x(&self) -> usize {
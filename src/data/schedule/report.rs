@@ -0,0 +1,217 @@
+use chrono::{NaiveDateTime, Utc};
+use serde_derive::{Serialize, Deserialize};
+use tokio::sync::RwLock;
+use std::{sync::Arc, path::PathBuf};
+
+use crate::{
+    data::{
+        schedule::{raw, Page},
+        json::{
+            self,
+            Path,
+            Saving,
+            DirectLoading,
+        }
+    },
+    SyncResult
+};
+
+
+/// # How parsing of a file went
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// # Every page was parsed
+    Ok,
+    /// # Some pages failed and there
+    /// was nothing to replace them with
+    Failed,
+    /// # Failed, pages from the previous update are used
+    Stale
+}
+
+/// # Parsing results of a single file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    /// # `ParsingError` of each failed page
+    pub errors: Vec<String>,
+    pub pages: usize,
+    pub formations: usize,
+    pub days: usize,
+    pub subjects: usize,
+    /// # Time spent parsing
+    pub took: std::time::Duration
+}
+impl FileReport {
+    /// # `pages` are the ones actually used,
+    /// previous ones for a stale file
+    pub fn new(
+        path: PathBuf,
+        status: FileStatus,
+        errors: Vec<String>,
+        pages: &[Page],
+        took: std::time::Duration
+    ) -> Self {
+        let formations = pages.iter().flat_map(|page| page.formations.iter());
+
+        Self {
+            path,
+            status,
+            errors,
+            pages: pages.len(),
+            formations: formations.clone().count(),
+            days: formations.clone().map(|form| form.days.len()).sum(),
+            subjects: formations
+                .flat_map(|form| form.days.iter())
+                .map(|day| day.subjects.len())
+                .sum(),
+            took
+        }
+    }
+}
+
+/// # Results of a single schedule type
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceReport {
+    pub name: String,
+    pub kind: raw::Kind,
+    /// # Content is the same as before,
+    /// `files` are from the update that parsed it
+    pub unchanged: bool,
+    /// # Fetching or unpacking gave up
+    pub failed: bool,
+    pub files: Vec<FileReport>
+}
+
+/// # Teacher names that were matched
+/// by similarity, or were similar but not matched.
+/// Add them to `aliases.json` to resolve.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NearMatch {
+    /// # Spelling from group schedules
+    pub name: String,
+    /// # Closest spelling from teacher schedules
+    pub candidate: String,
+    pub distance: usize,
+    /// # Treated as the same teacher
+    pub merged: bool
+}
+
+/// # Stores the report of the last update
+#[derive(Clone, Debug)]
+pub struct Report {
+    path: PathBuf,
+    pub updated: Arc<RwLock<Option<NaiveDateTime>>>,
+    pub sources: Arc<RwLock<Vec<SourceReport>>>,
+    pub near_matches: Arc<RwLock<Vec<NearMatch>>>,
+}
+impl json::Path for Report {
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+}
+
+impl json::ToMiddle<MiddleReport> for Report {
+    async fn to_middle(&self) -> MiddleReport {
+        MiddleReport {
+            path: self.path(),
+            updated: *self.updated.read().await,
+            sources: self.sources.read().await.clone(),
+            near_matches: self.near_matches.read().await.clone(),
+        }
+    }
+}
+impl json::Saving<MiddleReport> for Report {}
+impl Report {
+    pub fn default(path: PathBuf) -> Arc<Self> {
+        let this = Self {
+            path,
+            updated: Arc::new(RwLock::new(None)),
+            sources: Arc::new(RwLock::new(vec![])),
+            near_matches: Arc::new(RwLock::new(vec![])),
+        };
+
+        Arc::new(this)
+    }
+
+    fn from_middle(middle: Arc<MiddleReport>, path: PathBuf) -> Arc<Self> {
+        let this = Report {
+            path,
+            updated: Arc::new(RwLock::new(middle.updated)),
+            sources: Arc::new(RwLock::new(middle.sources.clone())),
+            near_matches: Arc::new(RwLock::new(middle.near_matches.clone())),
+        };
+
+        Arc::new(this)
+    }
+
+    async fn load(path: PathBuf) -> SyncResult<Arc<Self>> {
+        let middle = MiddleReport::load(path.clone()).await?;
+        let primary = Self::from_middle(middle, path);
+
+        Ok(primary)
+    }
+
+    pub async fn load_or_init(path: PathBuf) -> SyncResult<Arc<Self>> {
+        let this;
+
+        if path.exists() {
+            this = Self::load(path).await?;
+        } else {
+            this = Self::default(path);
+            this.save().await?;
+        }
+
+        Ok(this)
+    }
+
+    /// # Report of a source from the last update
+    pub async fn source(&self, name: &str) -> Option<SourceReport> {
+        self.sources.read().await
+            .iter()
+            .find(|source| source.name == name)
+            .cloned()
+    }
+
+    /// # Replace the report with the last update
+    /// `near_matches` stay from the previous one if `None`,
+    /// all fields are written before a single save
+    pub async fn set(
+        self: Arc<Self>,
+        sources: Vec<SourceReport>,
+        near_matches: Option<Vec<NearMatch>>
+    ) {
+        {
+            let mut updated_lock = self.updated.write().await;
+            let mut sources_lock = self.sources.write().await;
+            let mut near_matches_lock = self.near_matches.write().await;
+
+            *updated_lock = Some(Utc::now().naive_utc());
+            *sources_lock = sources;
+            if let Some(near_matches) = near_matches {
+                *near_matches_lock = near_matches;
+            }
+        }
+
+        self.poll_save()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MiddleReport {
+    #[serde(skip)]
+    path: PathBuf,
+    updated: Option<NaiveDateTime>,
+    sources: Vec<SourceReport>,
+    #[serde(default)]
+    near_matches: Vec<NearMatch>,
+}
+impl json::Path for MiddleReport {
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+}
+impl json::DirectSaving for MiddleReport {}
+impl json::DirectLoading for MiddleReport {}
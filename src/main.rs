@@ -17,6 +17,9 @@ use logger::Logger;
 use data::regex;
 
 
+/// # Replay archived downloads instead of running the server
+const REPLAY_ARG: &str = "--replay";
+
 static LOGGER: Logger = Logger;
 static mut REGEX: *const regex::Container = std::ptr::null();
 static mut DATA: *const data::Container = std::ptr::null();
@@ -33,6 +36,48 @@ pub fn options() -> &'static data::Container {
     unsafe { &*(DATA) }
 }
 
+/// # Set the globals once for every test
+/// Containers are leaked, so the globals stay
+/// valid while tests run in parallel.
+/// Their files go to a temporary directory
+/// instead of `./data`.
+#[cfg(test)]
+pub async fn init_test_globals() {
+    static INIT: tokio::sync::OnceCell<()> = tokio::sync::OnceCell::const_new();
+    static DATA_DIR: std::sync::OnceLock<tempfile::TempDir> = std::sync::OnceLock::new();
+
+    INIT.get_or_init(|| async {
+        let data_dir = DATA_DIR.get_or_init(|| tempfile::TempDir::new().unwrap());
+        let data_path = data_dir.path().to_path_buf();
+        let regex_own = Box::leak(Box::new(regex::Container::default()));
+        let data_own = Box::leak(Box::new(
+            data::Container::default_from_dir(data_path).await.unwrap()
+        ));
+
+        unsafe {
+            REGEX = regex_own;
+            DATA = data_own;
+        }
+    }).await;
+}
+
+
+/// # Reload the configuration on `SIGHUP`
+#[cfg(unix)]
+fn reload_on_hangup() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async {
+        let mut hangup = signal(SignalKind::hangup()).unwrap();
+
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading configuration");
+            if let Err(error) = options().reload().await {
+                error!("configuration is invalid, keeping the old one: {:?}", error);
+            }
+        }
+    });
+}
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> std::io::Result<()> {
@@ -40,15 +85,22 @@ async fn main() -> std::io::Result<()> {
 
     let data_path = [".", "data"].iter().collect();
 
-    let regex_own = regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
+    let data_own = match data::Container::default_from_dir(data_path).await {
+        Ok(data_own) => data_own,
+        Err(error) => {
+            error!("failed to load the configuration: {:?}", error);
+            return Err(std::io::Error::other("invalid configuration"));
+        }
+    };
+    // checked in `Settings::load_or_init`
+    let regex_own = regex::Container::from_patterns(&data_own.settings().regex).unwrap();
    
     unsafe {
         REGEX = &regex_own;
         DATA = &data_own;
     }
 
-    if options().schedule.index.types.len() < 1 {
+    if options().schedule.index().types.len() < 1 {
         let example = crate::data::schedule::raw::index::MiddleSchedule::example();
         let json_example = serde_json::to_string_pretty(&example).unwrap();
 
@@ -65,18 +117,33 @@ async fn main() -> std::io::Result<()> {
         ));
     }
 
-    options().schedule.index.clone().update_forever().await;
+    if std::env::args().any(|arg| arg == REPLAY_ARG) {
+        if let Err(error) = options().schedule.clone().replay().await {
+            error!("replay failed: {:?}", error);
+            return Err(std::io::Error::other("replay failed"));
+        }
+        return Ok(())
+    }
+
+    options().schedule.index().update_forever().await;
+
+    #[cfg(unix)]
+    reload_on_hangup();
 
-    let addr = options().settings.server.address.clone();
+    let addr = options().settings().server.address.clone();
     info!("http server will be ran on {}", addr);
     // start http server
     HttpServer::new(|| {
         App::new()
             .service(api::schedule::groups::get)
             .service(api::schedule::teachers::get)
+            .service(api::schedule::now::get)
             .service(api::schedule::updates)
             .service(api::schedule::updates_period)
             .service(api::schedule::updates_last)
+            .service(api::schedule::updates_health)
+            .service(api::schedule::updates_report)
+            .service(api::schedule::updates_trigger)
     })
         .bind(addr)?
         .run()
@@ -2,20 +2,25 @@ pub mod error;
 
 use chrono::NaiveDate;
 use error::MergeError;
-use std::ops::RangeInclusive;
+use std::{collections::HashSet, ops::RangeInclusive};
+use crate::parse;
 use crate::data::schedule::{
     raw,
     attender,
+    report::NearMatch,
     Page,
     Formation,
     Day,
     Subject,
     Attender,
     Cabinet,
+    Room,
 };
 
 
 const MAX_DISTANCE: usize = 3;
+/// # Unmerged names this close are still reported
+const NEAR_DISTANCE: usize = 5;
 
 
 /// # Complement two schedules
@@ -24,12 +29,15 @@ const MAX_DISTANCE: usize = 3;
 /// the other one doesn't have.
 /// This function complements both.
 /// 
+/// Returns teacher names that were matched
+/// by similarity or were close to a match.
+/// 
 /// (I AM SO PROUD OF THIS CODE,
 /// SMELLS AS SWEET AS INDIA)
 pub fn complement<'a>(
     groups: &'a mut Page, 
     teachers: &'a mut Page,
-) -> Result<(), MergeError<'a>> {
+) -> Result<Vec<NearMatch>, MergeError<'a>> {
     if groups.kind != raw::Kind::Groups {
         return Err(MergeError::InvalidKind(groups));
     }
@@ -44,6 +52,20 @@ pub fn complement<'a>(
         return Err(MergeError::NonOverlappingDates)
     }
 
+    let mut near_matches = vec![];
+    let aliases = crate::options().aliases();
+    let known_teachers = teachers.formations
+        .iter()
+        .map(|tchr| tchr.name.clone())
+        .collect::<HashSet<String>>();
+    // close names can't be the same teacher
+    // if both have their own teacher schedules
+    // or `aliases.json` marks them as distinct
+    let are_distinct = |a: &str, b: &str| a != b && (
+        (known_teachers.contains(a) && known_teachers.contains(b)) ||
+        aliases.are_distinct_teachers(a, b)
+    );
+
     for group in groups.formations.iter_mut() {
         for group_day in group.days.iter_mut() {
             for group_subject in group_day.subjects.iter_mut() {
@@ -53,8 +75,9 @@ pub fn complement<'a>(
                     }
 
                     // find teacher mapping
-                    let mut teacher = teachers.formations
+                    let nearest_teacher = teachers.formations
                         .iter_mut()
+                        .filter(|tchr| !are_distinct(&tchr.name, &group_attender.name))
                         .map(|tchr| (
                             strsim::damerau_levenshtein(
                                 &tchr.name,
@@ -63,18 +86,33 @@ pub fn complement<'a>(
                             tchr
                             )
                         )
-                        .filter(|(distance, _tchr)| *distance < MAX_DISTANCE)
                         // take one with least errors
                         .min_by(|(a_distance, _a_tchr), (b_distance, _b_tchr)| {
-                            a_distance.cmp(&b_distance)
-                        })
+                            a_distance.cmp(b_distance)
+                        });
+
+                    if let Some((distance, tchr)) = nearest_teacher.as_ref() {
+                        if (1..NEAR_DISTANCE).contains(distance) {
+                            near_matches.push(NearMatch {
+                                name: group_attender.name.clone(),
+                                candidate: tchr.name.clone(),
+                                distance: *distance,
+                                merged: *distance < MAX_DISTANCE
+                            });
+                        }
+                    }
+
+                    let mut teacher = nearest_teacher
+                        .filter(|(distance, _tchr)| *distance < MAX_DISTANCE)
                         .map(|(_distance, tchr)| tchr);
 
                     if teacher.is_none() {
                         let form = Formation {
                             raw: group_attender.raw.clone(),
                             recovered: true,
+                            stale: group.stale,
                             name: group_attender.name.clone(),
+                            code: None,
                             days: vec![]
                         };
                         teachers.formations.push(form);
@@ -104,7 +142,10 @@ pub fn complement<'a>(
                     // find the same subject within the teacher day
                     let mut teacher_subject = teacher_day.subjects
                         .iter_mut()
-                        .find(|tchr_subj| tchr_subj.num == group_subject.num);
+                        .find(|tchr_subj|
+                            tchr_subj.num == group_subject.num &&
+                            tchr_subj.is_same_subgroup_with(group_subject.subgroup)
+                        );
 
                     if teacher_subject.is_none() {
                         let subject = Subject {
@@ -112,8 +153,12 @@ pub fn complement<'a>(
                             recovered: true,
                             name: group_subject.name.clone(),
                             num: group_subject.num,
+                            subgroup: group_subject.subgroup,
                             format: group_subject.format,
-                            attenders: vec![]
+                            status: group_subject.status,
+                            attenders: vec![],
+                            start: group_subject.start,
+                            end: group_subject.end
                         };
                         teacher_day.subjects.push(subject);
                         teacher_subject = teacher_day.subjects.last_mut();
@@ -121,6 +166,10 @@ pub fn complement<'a>(
 
                     let teacher_subject = teacher_subject.unwrap();
 
+                    if teacher_subject.subgroup.is_none() && group_subject.subgroup.is_some() {
+                        teacher_subject.set_subgroup(group_subject.subgroup)
+                    }
+
                     // find the group as a teacher attender
                     let mut teacher_attender = teacher_subject.attenders
                         .iter_mut()
@@ -132,6 +181,7 @@ pub fn complement<'a>(
                             recovered: true,
                             kind: attender::Kind::Group,
                             name: group.name.clone(),
+                            subgroup: group_subject.subgroup,
                             cabinet: group_attender.cabinet.clone().swapped()
                         };
                         teacher_subject.attenders.push(attender);
@@ -144,16 +194,8 @@ pub fn complement<'a>(
                         teacher_subject.name = group_subject.name.clone()
                     }
 
-                    teacher_attender.cabinet.opposite = group_attender
-                        .cabinet
-                        .primary
-                        .as_ref()
-                        .map(|cab| cab.clone());
-                    group_attender.cabinet.opposite = teacher_attender
-                        .cabinet
-                        .primary
-                        .as_ref()
-                        .map(|cab| cab.clone());
+                    teacher_attender.cabinet.set_opposite_from(&group_attender.cabinet);
+                    group_attender.cabinet.set_opposite_from(&teacher_attender.cabinet);
                 }
             }
         }
@@ -176,7 +218,9 @@ pub fn complement<'a>(
                         let form = Formation {
                             raw: teacher_attender.raw.clone(),
                             recovered: true,
+                            stale: teacher.stale,
                             name: teacher_attender.name.clone(),
+                            code: parse::group::code(&teacher_attender.name),
                             days: vec![]
                         };
                         groups.formations.push(form);
@@ -206,7 +250,10 @@ pub fn complement<'a>(
                     // find the same subject within the group day
                     let mut group_subject = group_day.subjects
                         .iter_mut()
-                        .find(|group_subj| group_subj.num == teacher_subject.num);
+                        .find(|group_subj|
+                            group_subj.num == teacher_subject.num &&
+                            group_subj.is_same_subgroup_with(teacher_subject.subgroup)
+                        );
 
                     if group_subject.is_none() {
                         let subject = Subject {
@@ -214,8 +261,12 @@ pub fn complement<'a>(
                             recovered: true,
                             name: teacher_subject.name.clone(),
                             num: teacher_subject.num,
+                            subgroup: teacher_subject.subgroup,
                             format: teacher_subject.format,
-                            attenders: vec![]
+                            status: teacher_subject.status,
+                            attenders: vec![],
+                            start: teacher_subject.start,
+                            end: teacher_subject.end
                         };
                         group_day.subjects.push(subject);
                         group_subject = group_day.subjects.last_mut();
@@ -223,6 +274,10 @@ pub fn complement<'a>(
 
                     let group_subject = group_subject.unwrap();
 
+                    if group_subject.subgroup.is_none() && teacher_subject.subgroup.is_some() {
+                        group_subject.set_subgroup(teacher_subject.subgroup)
+                    }
+
                     // find the teacher as a group attender
                     let mut group_attender = group_subject.attenders
                         .iter_mut()
@@ -235,6 +290,9 @@ pub fn complement<'a>(
                             )
                         )
                         .filter(|(distance, _group_attender)| *distance < MAX_DISTANCE)
+                        .filter(|(_distance, group_attender)| {
+                            !are_distinct(&group_attender.name, &teacher.name)
+                        })
                         // take one with least errors
                         .min_by(|(a_distance, _a_group_attender), (b_distance, _b_group_attender)| {
                             a_distance.cmp(&b_distance)
@@ -247,6 +305,7 @@ pub fn complement<'a>(
                             recovered: true,
                             kind: attender::Kind::Teacher,
                             name: teacher.name.clone(),
+                            subgroup: teacher_subject.subgroup,
                             cabinet: teacher_attender.cabinet.clone().swapped()
                         };
                         group_subject.attenders.push(attender);
@@ -260,18 +319,10 @@ pub fn complement<'a>(
                     }
 
                     if group_attender.cabinet.opposite.is_none() {
-                        group_attender.cabinet.opposite = teacher_attender
-                            .cabinet
-                            .primary
-                            .as_ref()
-                            .map(|cab| cab.clone());
+                        group_attender.cabinet.set_opposite_from(&teacher_attender.cabinet);
                     }
                     if teacher_attender.cabinet.opposite.is_none() {
-                        teacher_attender.cabinet.opposite = group_attender
-                            .cabinet
-                            .primary
-                            .as_ref()
-                            .map(|cab| cab.clone());
+                        teacher_attender.cabinet.set_opposite_from(&group_attender.cabinet);
                     }
                 }
             }
@@ -292,7 +343,18 @@ pub fn complement<'a>(
         })
     });
 
-    Ok(())
+    near_matches.sort();
+    near_matches.dedup();
+
+    Ok(near_matches)
+}
+
+fn combine_rooms(dst: &mut Vec<Room>, src: Vec<Room>) {
+    for room in src {
+        if !dst.contains(&room) {
+            dst.push(room)
+        }
+    }
 }
 
 fn combine_cabinets(dst: &mut Cabinet, src: Cabinet) {
@@ -300,12 +362,14 @@ fn combine_cabinets(dst: &mut Cabinet, src: Cabinet) {
         if *dst_primary != src_primary {
             dst_primary.push_str(", ");
             dst_primary.push_str(&src_primary);
+            combine_rooms(&mut dst.primary_rooms, src.primary_rooms);
         }
     }
     if let (Some(dst_opposite), Some(src_opposite)) = (&mut dst.opposite, src.opposite) {
         if *dst_opposite != src_opposite {
             dst_opposite.push_str(", ");
             dst_opposite.push_str(&src_opposite);
+            combine_rooms(&mut dst.opposite_rooms, src.opposite_rooms);
         }
     }
 }
@@ -335,6 +399,8 @@ fn combine_subjects(dst: &mut Vec<Subject>, src: Vec<Subject>) {
             .iter_mut()
             .find(|dst_subject|
                 dst_subject.num == src_subject.num &&
+                dst_subject.name == src_subject.name &&
+                dst_subject.subgroup == src_subject.subgroup &&
                 dst_subject.format == src_subject.format
             )
         {
@@ -382,6 +448,7 @@ pub fn combine(
                 .iter_mut()
                 .find(|existing| existing.name == formation.name)
             {
+                existing_formation.stale |= formation.stale;
                 combine_days(
                     &mut existing_formation.days,
                     formation.days
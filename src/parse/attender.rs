@@ -81,10 +81,16 @@ pub fn teachers(string: &str) -> Option<(Range<usize>, Vec<schedule::Attender>)>
                 Some(trimmed_related_text)
             };
 
+            let primary_rooms = primary_cabinet
+                .as_deref()
+                .map(parse::cabinet::rooms)
+                .unwrap_or_default();
             let cabinet = schedule::Cabinet {
                 recovered: false,
                 primary: primary_cabinet,
-                opposite: None
+                opposite: None,
+                primary_rooms,
+                opposite_rooms: vec![]
             };
 
             let attender = schedule::Attender {
@@ -92,6 +98,7 @@ pub fn teachers(string: &str) -> Option<(Range<usize>, Vec<schedule::Attender>)>
                 recovered: false,
                 kind: kind.clone(),
                 name: valid_attender,
+                subgroup: None,
                 cabinet
             };
 
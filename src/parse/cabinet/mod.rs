@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests;
+
+use std::ops::Range;
+use crate::regexes;
+use crate::data::schedule::{Room, RoomKind};
+
+
+pub fn from_end<'a>(string: &'a str) -> Option<regex::Match<'a>> {
+    regexes().end_cabinet.find(string)
+}
+
+fn kind(string: &str, number: Option<&str>) -> RoomKind {
+    if let Some(captures) = regexes().room_kind.captures(string) {
+        if captures.name("sport").is_some() {
+            return RoomKind::SportHall
+        }
+        if captures.name("assembly").is_some() {
+            return RoomKind::AssemblyHall
+        }
+        if captures.name("remote").is_some() {
+            return RoomKind::Remote
+        }
+    }
+
+    if number.is_some() {
+        RoomKind::Classroom
+    } else {
+        RoomKind::Unknown
+    }
+}
+
+/// # Single room from a part of a cabinet value
+pub fn room(string: &str) -> Room {
+    let mut taken: Vec<Range<usize>> = vec![];
+
+    let building = regexes()
+        .room_building
+        .captures(string)
+        .and_then(|captures| {
+            taken.push(captures.get(0)?.range());
+            Some(captures.name("building")?.as_str().to_lowercase())
+        });
+
+    let floor = regexes()
+        .room_floor
+        .captures(string)
+        .or_else(|| regexes().room_floor_reverse.captures(string))
+        .and_then(|captures| {
+            taken.push(captures.get(0)?.range());
+            captures.name("floor")?.as_str().parse::<u32>().ok()
+        });
+
+    // building and floor numbers aren't room numbers
+    let number = regexes()
+        .room_number
+        .find_iter(string)
+        .find(|num| !taken.iter().any(|range| range.contains(&num.start())))
+        .map(|num| num.as_str().to_lowercase());
+
+    Room {
+        raw: string.to_string(),
+        kind: kind(string, number.as_deref()),
+        number,
+        building,
+        floor
+    }
+}
+
+/// # Every room of a cabinet value
+/// Rooms are separated by `,` or `;`,
+/// like in cabinets of combined schedules
+pub fn rooms(string: &str) -> Vec<Room> {
+    string
+        .split([',', ';'])
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .map(room)
+        .collect()
+}
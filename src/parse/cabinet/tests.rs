@@ -0,0 +1,56 @@
+use super::*;
+
+
+fn expected(
+    raw: &str,
+    kind: RoomKind,
+    number: Option<&str>,
+    building: Option<&str>,
+    floor: Option<u32>
+) -> Room {
+    Room {
+        raw: raw.to_string(),
+        kind,
+        number: number.map(|num| num.to_string()),
+        building: building.map(|building| building.to_string()),
+        floor
+    }
+}
+
+#[tokio::test]
+async fn test_room() {
+    crate::init_test_globals().await;
+
+    assert_eq!(room("каб 101"), expected("каб 101", RoomKind::Classroom, Some("101"), None, None));
+    assert_eq!(room("17а"), expected("17а", RoomKind::Classroom, Some("17а"), None, None));
+    assert_eq!(room("каб ?"), expected("каб ?", RoomKind::Unknown, None, None, None));
+    assert_eq!(room("спорт зал"), expected("спорт зал", RoomKind::SportHall, None, None, None));
+    assert_eq!(room("актовый зал"), expected("актовый зал", RoomKind::AssemblyHall, None, None, None));
+    assert_eq!(room("дистант"), expected("дистант", RoomKind::Remote, None, None, None));
+    assert_eq!(
+        room("корп. 2 каб 101"),
+        expected("корп. 2 каб 101", RoomKind::Classroom, Some("101"), Some("2"), None)
+    );
+    assert_eq!(
+        room("каб 301 эт. 3"),
+        expected("каб 301 эт. 3", RoomKind::Classroom, Some("301"), None, Some(3))
+    );
+    assert_eq!(
+        room("3 этаж каб 301"),
+        expected("3 этаж каб 301", RoomKind::Classroom, Some("301"), None, Some(3))
+    );
+}
+
+#[tokio::test]
+async fn test_rooms() {
+    crate::init_test_globals().await;
+
+    assert_eq!(
+        rooms("каб 101, спорт зал"),
+        vec![
+            expected("каб 101", RoomKind::Classroom, Some("101"), None, None),
+            expected("спорт зал", RoomKind::SportHall, None, None, None),
+        ]
+    );
+    assert_eq!(rooms(""), vec![]);
+}
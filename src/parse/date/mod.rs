@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use chrono::{Datelike, NaiveDate};
 use crate::regexes;
 
@@ -123,7 +126,61 @@ pub fn day(string: &str) -> Result<u32, DayError> {
     Ok(num)
 }
 
-pub fn whole(date: &str) -> Result<NaiveDate, WholeError> {
+/// # Day and month with an optional year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Partial {
+    pub day: u32,
+    pub month: u32,
+    /// # Only if it was written in the date
+    pub year: Option<i32>
+}
+impl Partial {
+    pub fn with_year(&self, year: i32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, self.month, self.day)
+    }
+
+    /// # Closest to `today` if there's no year
+    fn nearest(&self, today: NaiveDate) -> Option<NaiveDate> {
+        if let Some(year) = self.year {
+            return self.with_year(year)
+        }
+
+        [today.year() - 1, today.year(), today.year() + 1]
+            .into_iter()
+            .filter_map(|year| self.with_year(year))
+            .min_by_key(|date| (*date - today).num_days().abs())
+    }
+
+    /// # Not earlier than `prev` if there's no year
+    fn after(&self, prev: NaiveDate) -> Option<NaiveDate> {
+        if let Some(year) = self.year {
+            return self.with_year(year)
+        }
+
+        self.with_year(prev.year())
+            .filter(|date| *date >= prev)
+            .or_else(|| self.with_year(prev.year() + 1))
+    }
+
+    /// # Not later than `next` if there's no year
+    fn before(&self, next: NaiveDate) -> Option<NaiveDate> {
+        if let Some(year) = self.year {
+            return self.with_year(year)
+        }
+
+        self.with_year(next.year())
+            .filter(|date| *date <= next)
+            .or_else(|| self.with_year(next.year() - 1))
+    }
+}
+
+/// # Parse a date, the year is optional
+/// 
+/// ## Input examples
+/// - `"01.09"`
+/// - `"01.09.24"`
+/// - `"01.09.2024"`
+pub fn partial(date: &str) -> Result<Partial, WholeError> {
     let parts = regexes().nonword.split(date).collect::<Vec<&str>>();
     if parts.len() < 2 {
         return Err(WholeError::NotEnoughData)
@@ -131,25 +188,44 @@ pub fn whole(date: &str) -> Result<NaiveDate, WholeError> {
         return Err(WholeError::TooMuchData)
     }
 
-    if parts.len() == 2 {
-        let d = parts.get(0).unwrap();
-        let m = parts.get(1).unwrap();
-        let y = chrono::Utc::now().year();
+    let partial = Partial {
+        day: day(parts[0])?,
+        month: month(parts[1])?,
+        year: parts.get(2).map(|y| year(y)).transpose()?
+    };
 
-        return Ok(
-            NaiveDate::from_ymd_opt(y, month(m)?, day(d)?)
-            .ok_or(WholeError::DoesNotExist)?
-        )
-    } else if parts.len() == 3 {
-        let d = parts.get(0).unwrap();
-        let m = parts.get(1).unwrap();
-        let y = parts.get(2).unwrap();
+    // 29.02 may only exist in some years
+    if partial.with_year(partial.year.unwrap_or(2000)).is_none() {
+        return Err(WholeError::DoesNotExist)
+    }
+
+    Ok(partial)
+}
 
-        return Ok(
-            NaiveDate::from_ymd_opt(year(y)?, month(m)?, day(d)?)
-            .ok_or(WholeError::DoesNotExist)?
-        )
+/// # Fill missing years of dates from a single row
+/// Dates go left to right, so every next one
+/// is not earlier than the previous, even across New Year.
+/// The row is anchored at the first date with a year,
+/// or at the year that puts the first date closest to `today`.
+pub fn infer(partials: &[Partial], today: NaiveDate) -> Result<Vec<NaiveDate>, WholeError> {
+    let Some(first) = partials.first() else {
+        return Ok(vec![])
+    };
+
+    let (anchor_idx, anchor) = match partials.iter().position(|partial| partial.year.is_some()) {
+        Some(idx) => (idx, partials[idx].nearest(today)),
+        None => (0, first.nearest(today))
+    };
+    let anchor = anchor.ok_or(WholeError::DoesNotExist)?;
+
+    let mut dates = vec![anchor; partials.len()];
+
+    for idx in (anchor_idx + 1)..partials.len() {
+        dates[idx] = partials[idx].after(dates[idx - 1]).ok_or(WholeError::DoesNotExist)?;
+    }
+    for idx in (0..anchor_idx).rev() {
+        dates[idx] = partials[idx].before(dates[idx + 1]).ok_or(WholeError::DoesNotExist)?;
     }
 
-    unreachable!();
-}
\ No newline at end of file
+    Ok(dates)
+}
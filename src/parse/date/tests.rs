@@ -0,0 +1,66 @@
+use super::*;
+
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn dm(day: u32, month: u32) -> Partial {
+    Partial { day, month, year: None }
+}
+
+/// "29.12 - 04.01"
+fn new_year_week() -> Vec<Partial> {
+    vec![dm(29, 12), dm(30, 12), dm(31, 12), dm(2, 1), dm(3, 1), dm(4, 1)]
+}
+
+#[test]
+fn test_infer_before_new_year() {
+    let dates = infer(&new_year_week(), ymd(2025, 12, 28)).unwrap();
+
+    assert_eq!(dates.first(), Some(&ymd(2025, 12, 29)));
+    assert_eq!(dates.last(), Some(&ymd(2026, 1, 4)));
+}
+
+#[test]
+fn test_infer_after_new_year() {
+    // last week's sheet is still up
+    let dates = infer(&new_year_week(), ymd(2026, 1, 2)).unwrap();
+
+    assert_eq!(dates.first(), Some(&ymd(2025, 12, 29)));
+    assert_eq!(dates.last(), Some(&ymd(2026, 1, 4)));
+
+    // and when the whole week is already next year
+    let dates = infer(&[dm(5, 1), dm(10, 1)], ymd(2025, 12, 30)).unwrap();
+
+    assert_eq!(dates, vec![ymd(2026, 1, 5), ymd(2026, 1, 10)]);
+}
+
+#[test]
+fn test_infer_explicit_year() {
+    let partials = vec![
+        dm(30, 12),
+        dm(31, 12),
+        Partial { day: 2, month: 1, year: Some(2025) },
+        dm(3, 1)
+    ];
+    // explicit year wins over the current one
+    let dates = infer(&partials, ymd(2030, 6, 1)).unwrap();
+
+    assert_eq!(dates, vec![
+        ymd(2024, 12, 30),
+        ymd(2024, 12, 31),
+        ymd(2025, 1, 2),
+        ymd(2025, 1, 3)
+    ]);
+}
+
+#[tokio::test]
+async fn test_partial() {
+    crate::init_test_globals().await;
+
+    assert_eq!(partial("01.09").unwrap(), dm(1, 9));
+    assert_eq!(partial("01.09.25").unwrap().year, Some(2025));
+    assert_eq!(partial("29.02").unwrap(), dm(29, 2));
+    assert!(partial("31.02").is_err());
+}
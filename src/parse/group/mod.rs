@@ -3,13 +3,19 @@ mod tests;
 
 use std::ops::Range;
 use crate::regexes;
+use crate::data::schedule::GroupCode;
 
 
 pub fn validate_unchecked(string: &str) -> String {
     let without_punctiation = regexes().nonword.replace_all(string, "").to_string();
     let mut capitalized = without_punctiation.to_uppercase();
-    if capitalized.chars().nth(1).unwrap() != 'К' {
-        capitalized.insert(1, 'К');
+    if !regexes().complete_group {
+        return capitalized
+    }
+    if let Some((idx, second)) = capitalized.char_indices().nth(1) {
+        if second != 'К' {
+            capitalized.insert(idx, 'К');
+        }
     }
     capitalized
 }
@@ -19,6 +25,18 @@ pub fn validate(string: &str) -> Option<String> {
     Some(validate_unchecked(matched))
 }
 
+/// # Course, speciality and number of a group name
+/// Taken from the `course`, `speciality` and `number`
+/// groups of the `group` pattern, `None` if one is missing
+pub fn code(name: &str) -> Option<GroupCode> {
+    let captures = regexes().group.captures(name)?;
+    let course = captures.name("course")?.as_str().parse::<u32>().ok()?;
+    let speciality = captures.name("speciality")?.as_str().to_uppercase();
+    let number = captures.name("number")?.as_str().parse::<u32>().ok()?;
+
+    Some(GroupCode { course, speciality, number })
+}
+
 pub fn validate_all(string: &str) -> Vec<String> {
     let mut output = vec![];
     let matched = regexes().group.find_iter(string);
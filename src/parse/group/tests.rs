@@ -1,9 +1,8 @@
 use super::*;
 
-#[test]
-fn test_validate() {
-    let regex_own = crate::data::regex::Container::default();
-    unsafe { crate::REGEX = &regex_own };
+#[tokio::test]
+async fn test_validate() {
+    crate::init_test_globals().await;
 
     assert_eq!(validate("3кдд48"), Some("3КДД48".to_string()));
     assert_eq!(validate("3-кдд-48"), Some("3КДД48".to_string()));
@@ -16,10 +15,27 @@ fn test_validate() {
     assert_eq!(validate("жив каб 17а"), None);
 }
 
-#[test]
-fn test_multi() {
-    let regex_own = crate::data::regex::Container::default();
-    unsafe { crate::REGEX = &regex_own };
+#[tokio::test]
+async fn test_code() {
+    crate::init_test_globals().await;
+
+    let expected = |course, speciality: &str, number| Some(GroupCode {
+        course,
+        speciality: speciality.to_string(),
+        number
+    });
+
+    assert_eq!(code("4КРД36"), expected(4, "КРД", 36));
+    assert_eq!(code("1КДД2"), expected(1, "КДД", 2));
+    assert_eq!(code("жив каб 17а"), None);
+    assert!(code("4КРД36").unwrap().matches(Some(4), Some("крд")));
+    assert!(code("4КРД36").unwrap().matches(None, None));
+    assert!(!code("4КРД36").unwrap().matches(Some(2), None));
+}
+
+#[tokio::test]
+async fn test_multi() {
+    crate::init_test_globals().await;
 
     assert_eq!(
         multi("3дд48 жив каб 17а"),
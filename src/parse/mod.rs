@@ -2,15 +2,16 @@ pub mod sheet;
 pub mod css;
 pub mod node;
 pub mod date;
+pub mod status;
+pub mod subgroup;
 pub mod group;
 pub mod teacher;
 pub mod attender;
 pub mod subject;
 pub mod cabinet;
 
-use std::path::PathBuf;
-use crate::data::schedule;
-use crate::data::schedule::raw::Kind;
+use crate::data::schedule::File;
+use crate::data::schedule::raw::{Kind, Limits};
 use crate::lifetime;
 
 
@@ -29,20 +30,20 @@ pub async fn dom_from_string(string: &String)
     return Ok(dom)
 }
 
-async fn generic(paths: &[PathBuf], kind: Kind)
-    -> Vec<Result<schedule::Page, sheet::ParsingError>>
+async fn generic(files: &[File], kind: Kind, limits: &Limits)
+    -> Vec<sheet::Parsed>
 {
-    sheet::from_paths(paths, kind).await
+    sheet::from_files(files, kind, limits).await
 }
 
-pub async fn groups(paths: &[PathBuf])
-    -> Vec<Result<schedule::Page, sheet::ParsingError>>
+pub async fn groups(files: &[File], limits: &Limits)
+    -> Vec<sheet::Parsed>
 {
-    generic(paths, Kind::Groups).await
+    generic(files, Kind::Groups, limits).await
 }
 
-pub async fn teachers(paths: &[PathBuf])
-    -> Vec<Result<schedule::Page, sheet::ParsingError>>
+pub async fn teachers(files: &[File], limits: &Limits)
+    -> Vec<sheet::Parsed>
 {
-    generic(paths, Kind::Teachers).await
+    generic(files, Kind::Teachers, limits).await
 }
\ No newline at end of file
@@ -2,15 +2,18 @@ pub mod text {
     use html_parser::Node;
     use htmlescape::decode_html;
 
+    /// # Elements that put their content on a new line
+    const LINE_BREAKS: [&str; 4] = ["br", "p", "div", "li"];
+
+    fn decoded(text: &str) -> String {
+        decode_html(text).unwrap_or(text.to_owned())
+    }
+
     pub fn nested_as_vec(node: &Node) -> Vec<String> {
         let mut texts = vec![];
 
-        if node.text().is_some() {
-            if let Ok(decoded) = decode_html(node.text().unwrap()) {
-                texts.push(decoded)
-            } else {
-                texts.push(node.text().unwrap().to_owned())
-            }
+        if let Some(text) = node.text() {
+            texts.push(decoded(text))
         }
 
         if node.element().is_none() {
@@ -48,4 +51,61 @@ pub mod text {
         let texts = nested_as_vec(node);
         texts.join(sep)
     }
+
+    fn collect_lines(node: &Node, lines: &mut Vec<Vec<String>>) {
+        if let Some(text) = node.text() {
+            lines.last_mut().unwrap().push(decoded(text))
+        }
+
+        let Some(element) = node.element() else {
+            return
+        };
+        let is_line_break = LINE_BREAKS.contains(&element.name.as_str());
+
+        if is_line_break {
+            lines.push(vec![])
+        }
+
+        for child in element.children.iter() {
+            collect_lines(child, lines)
+        }
+
+        if is_line_break {
+            lines.push(vec![])
+        }
+    }
+
+    /// # Get text lines from all child nodes
+    /// 
+    /// Lines are split by `< br >` and block elements,
+    /// texts within one line are joined with `sep`,
+    /// empty lines are dropped
+    /// 
+    /// ## Example input
+    /// 
+    /// ```notrust
+    /// node = {
+    ///     < td >
+    ///         text1 
+    ///         < span >
+    ///             text2
+    ///         < /span >
+    ///         < br >
+    ///         text3
+    ///     < /td >
+    /// }
+    /// sep = " "
+    /// ```
+    /// ## Example output
+    /// - `["text1 text2", "text3"]`
+    pub fn nested_as_lines(node: &Node, sep: &str) -> Vec<String> {
+        let mut lines = vec![vec![]];
+        collect_lines(node, &mut lines);
+
+        lines
+            .into_iter()
+            .map(|texts| texts.join(sep).trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
 }
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use colors_transform::Color;
 use std::path::PathBuf;
 use crate::data::{self, schedule::raw::table};
@@ -16,6 +19,9 @@ const HEIGHT: &str = "height";
 const GRID_CONTAINER: &str = "grid-container";
 const FREEZEBAR_CELL: &str = "freezebar-cell";
 const BACKGROUND_COLOR: &str = "background-color";
+const TEXT_DECORATION: &str = "text-decoration";
+const TEXT_DECORATION_LINE: &str = "text-decoration-line";
+const LINE_THROUGH: &str = "line-through";
 const DEFAULT_CELL_COLOR: palette::Srgb = {
     palette::Srgb::new(255.0, 255.0, 255.0)
 };
@@ -26,6 +32,7 @@ const ZERO: &str = "0";
 #[error("html parsing error")]
 pub enum ParsingError {
     LoadIO(std::io::Error),
+    LoadUtf8(std::str::Utf8Error),
     LoadHtmlParser(html_parser::Error),
     NoTbody,
 }
@@ -34,12 +41,33 @@ impl From<std::io::Error> for ParsingError {
         Self::LoadIO(value)
     }
 }
+impl From<std::str::Utf8Error> for ParsingError {
+    fn from(value: std::str::Utf8Error) -> Self {
+        Self::LoadUtf8(value)
+    }
+}
 impl From<html_parser::Error> for ParsingError {
     fn from(value: html_parser::Error) -> Self {
         Self::LoadHtmlParser(value)
     }
 }
 
+/// # Which Google product exported the HTML
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// # Google Sheets
+    /// A single `div.grid-container > table`,
+    /// colors are set by CSS classes,
+    /// frozen rows and columns are marked
+    /// with `freezebar-cell`.
+    Sheets,
+    /// # Google Docs
+    /// One or more `table`s inside `body`,
+    /// cell text is split into `<p>` and `<span>`s,
+    /// colors are set by CSS classes or inline styles.
+    Docs
+}
+
 pub struct Parser {
     pub string_dom: String,
     pub dom: html_parser::Dom
@@ -54,17 +82,54 @@ impl Parser {
         Ok(this)
     }
 
+    pub async fn from_bytes(bytes: &[u8]) -> Result<Self, ParsingError> {
+        let string = std::str::from_utf8(bytes)?.to_string();
+        let this = Self::from_string(string).await?;
+        Ok(this)
+    }
+
     pub async fn from_path(path: &PathBuf) -> Result<Self, ParsingError> {
         let string = tokio::fs::read_to_string(path).await?;
         let this = Self::from_string(string).await?;
         Ok(this)
     }
 
+    /// # Find elements with this name at any depth
+    /// Doesn't look inside of the found elements.
+    fn find_nested<'a>(
+        nodes: &'a [html_parser::Node],
+        name: &str,
+        found: &mut Vec<&'a html_parser::Node>
+    ) {
+        for node in nodes {
+            let Some(elm) = node.element() else { continue };
+            if elm.name == name {
+                found.push(node);
+            } else {
+                Self::find_nested(&elm.children, name, found);
+            }
+        }
+    }
+
     fn style(&self) -> Option<&html_parser::Node> {
-        self.dom.children.iter().find(|node| {
-            let Some(elm) = node.element() else { return false };
-            elm.name == STYLE
-        })
+        let mut found = vec![];
+        Self::find_nested(&self.dom.children, STYLE, &mut found);
+        found.first().copied()
+    }
+
+    pub fn layout(&self) -> Option<Layout> {
+        if self.main_div().is_some() {
+            return Some(Layout::Sheets);
+        }
+
+        let mut tables = vec![];
+        Self::find_nested(&self.dom.children, TABLE, &mut tables);
+
+        if tables.is_empty() {
+            None
+        } else {
+            Some(Layout::Docs)
+        }
     }
 
     fn main_div(&self) -> Option<&html_parser::Node> {
@@ -85,19 +150,73 @@ impl Parser {
         })
     }
 
-    fn main_tbody(&self) -> Option<&html_parser::Node> {
-        let node = self.main_table()?;
-        let elm = node.element()?;
+    fn tbody_of(table: &html_parser::Node) -> Option<&html_parser::Node> {
+        let elm = table.element()?;
         elm.children.iter().find(|node| {
             let Some(elm) = node.element() else { return false };
             elm.name == TBODY
         })
     }
 
+    fn main_tbody(&self) -> Option<&html_parser::Node> {
+        Self::tbody_of(self.main_table()?)
+    }
+
+    /// # Every `tbody` that holds schedule rows
+    /// Docs may split a long table into several,
+    /// their rows are put one after another.
+    fn tbodies(&self) -> Vec<&html_parser::Element> {
+        match self.layout() {
+            Some(Layout::Sheets) => self.main_tbody()
+                .and_then(|tb| tb.element())
+                .into_iter()
+                .collect(),
+            Some(Layout::Docs) => {
+                let mut tables = vec![];
+                Self::find_nested(&self.dom.children, TABLE, &mut tables);
+                tables.into_iter()
+                    .filter_map(|table| Self::tbody_of(table).or(Some(table)))
+                    .filter_map(|tb| tb.element())
+                    .collect()
+            },
+            None => vec![]
+        }
+    }
+
+    fn color_from_string(string: &str) -> Option<palette::Srgb> {
+        let ct_rgb = if string.contains('(') && string.contains(')') {
+            string.parse::<colors_transform::Rgb>().ok()?
+        } else {
+            colors_transform::Rgb::from_hex_str(string).ok()?
+        };
+
+        Some(palette::Srgb::new(
+            ct_rgb.get_red(),
+            ct_rgb.get_green(),
+            ct_rgb.get_blue()
+        ))
+    }
+
+    /// # Background color from the `style` attribute
+    fn inline_color(style_string: &str) -> Option<palette::Srgb> {
+        let mut input = cssparser::ParserInput::new(style_string);
+        let mut parser = cssparser::Parser::new(&mut input);
+        let props = parse::css::Properties::hashmap(&mut parser);
+        let values = props.get(BACKGROUND_COLOR)?;
+        let first = parse::css::values_to_strings(values).first()?.clone();
+
+        Self::color_from_string(&first)
+    }
+
     fn cell_color_or_default(
         styles: Option<&data::css::SelectorVec>,
-        classes: &Vec<String>
+        classes: &[String],
+        inline_style: Option<&String>
     ) -> palette::Srgb {
+        if let Some(color) = inline_style.and_then(|style| Self::inline_color(style)) {
+            return color;
+        }
+
         let Some(styles) = styles else { return DEFAULT_CELL_COLOR };
         let Some(values) = parse::css::get_key_from_classes(
             BACKGROUND_COLOR,
@@ -111,29 +230,83 @@ impl Parser {
             return DEFAULT_CELL_COLOR
         };
 
-        let ct_rgb;
+        Self::color_from_string(&first).unwrap_or(DEFAULT_CELL_COLOR)
+    }
 
-        if first.contains('(') && first.contains(')') {
-            let Some(rgb) = first.parse::<colors_transform::Rgb>().ok() else {
-                return DEFAULT_CELL_COLOR
-            };
-            ct_rgb = rgb;
-        } else {
-            let Some(rgb) = colors_transform::Rgb::from_hex_str(&first).ok() else {
-                return DEFAULT_CELL_COLOR
-            };
-            ct_rgb = rgb;
+    fn has_line_through(values: &Vec<data::css::Value>) -> bool {
+        parse::css::values_to_strings(values)
+            .iter()
+            .any(|value| value == LINE_THROUGH)
+    }
+
+    /// # `text-decoration: line-through`
+    /// either in the `style` attribute or in a class
+    fn is_struck_through(
+        styles: Option<&data::css::SelectorVec>,
+        classes: &[String],
+        inline_style: Option<&String>
+    ) -> bool {
+        if let Some(style_string) = inline_style {
+            let mut input = cssparser::ParserInput::new(style_string);
+            let mut parser = cssparser::Parser::new(&mut input);
+            let props = parse::css::Properties::hashmap(&mut parser);
+            let is_inline = [TEXT_DECORATION, TEXT_DECORATION_LINE]
+                .iter()
+                .filter_map(|key| props.get(*key))
+                .any(Self::has_line_through);
+
+            if is_inline {
+                return true;
+            }
         }
 
-        palette::Srgb::new(
-            ct_rgb.get_red(),
-            ct_rgb.get_green(),
-            ct_rgb.get_blue()
-        )
+        let Some(styles) = styles else { return false };
+
+        [TEXT_DECORATION, TEXT_DECORATION_LINE]
+            .iter()
+            .filter_map(|key| parse::css::get_key_from_classes(key, classes, styles))
+            .any(Self::has_line_through)
+    }
+
+    /// # Is all text inside of `node` struck through
+    /// Google Docs put `line-through` on classes
+    /// of `<span>`s inside of the cell, not on the `<td>`.
+    /// `None` if there is no text at all.
+    fn is_text_struck_through(
+        styles: Option<&data::css::SelectorVec>,
+        node: &html_parser::Node,
+        is_parent_struck: bool
+    ) -> Option<bool> {
+        match node {
+            html_parser::Node::Text(text) => {
+                let is_blank = text.replace("&nbsp;", " ").trim().is_empty();
+                (!is_blank).then_some(is_parent_struck)
+            },
+            html_parser::Node::Element(elm) => {
+                let is_struck = is_parent_struck || Self::is_struck_through(
+                    styles,
+                    &elm.classes,
+                    elm.attributes.get(STYLE).and_then(|opt| opt.as_ref())
+                );
+
+                let mut has_text = false;
+                for child in elm.children.iter() {
+                    match Self::is_text_struck_through(styles, child, is_struck) {
+                        Some(false) => return Some(false),
+                        Some(true) => has_text = true,
+                        None => ()
+                    }
+                }
+
+                has_text.then_some(true)
+            },
+            _ => None
+        }
     }
 
     pub async fn parse(&self) -> Result<Vec<Vec<table::Cell>>, ParsingError> {
-        let Some(tbody) = self.main_tbody().map(|tb| tb.element()).flatten() else {
+        let tbodies = self.tbodies();
+        if tbodies.is_empty() {
             return Err(ParsingError::NoTbody)
         };
 
@@ -153,7 +326,11 @@ impl Parser {
             styles = Some(parse::css::Sheet::selectors(&mut styles_parser));
         }
 
-        'row: for node_row in tbody.children.iter() {
+        let node_rows = tbodies
+            .into_iter()
+            .flat_map(|tbody| tbody.children.iter());
+
+        'row: for node_row in node_rows {
             let Some(elm_row) = node_row.element() else { continue };
 
             let is_not_tr = elm_row.name != TR;
@@ -250,11 +427,21 @@ impl Parser {
                     .unwrap_or(0);
 
                 let text = parse::node::text::nested_as_string(node_cell, " ");
+                let lines = parse::node::text::nested_as_lines(node_cell, " ");
 
+                let inline_style = elm_cell.attributes
+                    .get(STYLE)
+                    .and_then(|opt| opt.as_ref());
                 let color = Self::cell_color_or_default(
                     styles.as_ref(),
-                    &elm_cell.classes
+                    &elm_cell.classes,
+                    inline_style
                 );
+                let strikethrough = Self::is_struck_through(
+                    styles.as_ref(),
+                    &elm_cell.classes,
+                    inline_style
+                ) || Self::is_text_struck_through(styles.as_ref(), node_cell, false) == Some(true);
 
                 let cell = table::Cell {
                     x,
@@ -262,7 +449,9 @@ impl Parser {
                     colspan,
                     rowspan,
                     text,
-                    color
+                    lines,
+                    color,
+                    strikethrough
                 };
 
                 if cell.does_hit_next_rows() {
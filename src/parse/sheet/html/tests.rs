@@ -0,0 +1,80 @@
+use super::*;
+
+
+const SHEETS_HTML: &str = r#"<meta http-equiv="Content-Type" content="text/html; charset=utf-8">
+<style type="text/css">.ritz .waffle .s1{background-color:#fce5cd;}.ritz .waffle .s2{text-decoration:line-through;}</style>
+<div class="ritz grid-container" dir="ltr">
+<table class="waffle" cellspacing="0" cellpadding="0">
+<thead><tr><th class="row-header freezebar-origin-ltr"></th></tr></thead>
+<tbody>
+<tr style="height: 20px"><td class="s0">4КРД36</td><td class="s1">Математика</td><td class="s2">Физика</td><td style="text-decoration: line-through">Химия</td><td class="s0">Математика Иванова А.А. 101<br>Физика Петров Б.Б. 102</td></tr>
+<tr style="height: 2px"><td class="s0"></td><td class="s0"></td></tr>
+<tr><td class="freezebar-cell"></td><td class="freezebar-cell"></td></tr>
+</tbody>
+</table>
+</div>"#;
+
+const DOCS_HTML: &str = r#"<html><head><meta content="text/html; charset=UTF-8" http-equiv="content-type">
+<style type="text/css">.c3{background-color:#d9ead3;padding:5pt}.c1{font-weight:700}.c7{text-decoration:line-through}</style>
+</head><body class="c5 doc-content">
+<p class="c2"><span class="c1">Расписание</span></p>
+<table class="c8"><tr class="c4">
+<td class="c3" colspan="1" rowspan="2"><p class="c2"><span class="c1">4КРД36</span></p></td>
+<td class="c6" style="background-color:#fce5cd"><p><span>Математика</span></p><p><span>Иванова А.А.</span></p></td>
+<td class="c6"><p><span class="c7">Физика</span></p><p><span class="c7">Петров Б.Б.</span></p></td>
+</tr><tr class="c4"><td class="c6"><p><span></span></p></td>
+<td class="c6"><p><span class="c7">Химия</span> <span>Сидоров В.В.</span></p></td></tr></table>
+<p class="c2"><span></span></p>
+<table class="c8"><tbody><tr class="c4"><td class="c6"><p><span>пн 01.01</span></p></td></tr></tbody></table>
+</body></html>"#;
+
+
+#[tokio::test]
+async fn test_sheets() {
+    let parser = Parser::from_string(SHEETS_HTML.to_string()).await.unwrap();
+    assert_eq!(parser.layout(), Some(Layout::Sheets));
+
+    let schema = parser.parse().await.unwrap();
+    let white = palette::Srgb::new(255.0, 255.0, 255.0);
+    let peach = palette::Srgb::new(252.0, 229.0, 205.0);
+
+    // low rows and freezebar rows are skipped,
+    // strikethrough comes from a class or the inline style,
+    // lines are split by `<br>`
+    assert_eq!(schema.len(), 1);
+    assert_eq!(schema[0], vec![
+        table::Cell { x: 0, y: 0, colspan: 0, rowspan: 0, text: "4КРД36".to_string(), lines: vec!["4КРД36".to_string()], color: white, strikethrough: false },
+        table::Cell { x: 1, y: 0, colspan: 0, rowspan: 0, text: "Математика".to_string(), lines: vec!["Математика".to_string()], color: peach, strikethrough: false },
+        table::Cell { x: 2, y: 0, colspan: 0, rowspan: 0, text: "Физика".to_string(), lines: vec!["Физика".to_string()], color: white, strikethrough: true },
+        table::Cell { x: 3, y: 0, colspan: 0, rowspan: 0, text: "Химия".to_string(), lines: vec!["Химия".to_string()], color: white, strikethrough: true },
+        table::Cell { x: 4, y: 0, colspan: 0, rowspan: 0, text: "Математика Иванова А.А. 101 Физика Петров Б.Б. 102".to_string(), lines: vec!["Математика Иванова А.А. 101".to_string(), "Физика Петров Б.Б. 102".to_string()], color: white, strikethrough: false },
+    ]);
+}
+
+#[tokio::test]
+async fn test_docs() {
+    let parser = Parser::from_string(DOCS_HTML.to_string()).await.unwrap();
+    assert_eq!(parser.layout(), Some(Layout::Docs));
+
+    let schema = parser.parse().await.unwrap();
+    let white = palette::Srgb::new(255.0, 255.0, 255.0);
+    let green = palette::Srgb::new(217.0, 234.0, 211.0);
+    let peach = palette::Srgb::new(252.0, 229.0, 205.0);
+
+    // rows of both tables are put one after another,
+    // strikethrough of every span counts for the cell,
+    // a partly struck one stays as is
+    assert_eq!(schema.len(), 3);
+    assert_eq!(schema[0], vec![
+        table::Cell { x: 0, y: 0, colspan: 1, rowspan: 2, text: "4КРД36".to_string(), lines: vec!["4КРД36".to_string()], color: green, strikethrough: false },
+        table::Cell { x: 1, y: 0, colspan: 0, rowspan: 0, text: "Математика Иванова А.А.".to_string(), lines: vec!["Математика".to_string(), "Иванова А.А.".to_string()], color: peach, strikethrough: false },
+        table::Cell { x: 2, y: 0, colspan: 0, rowspan: 0, text: "Физика Петров Б.Б.".to_string(), lines: vec!["Физика".to_string(), "Петров Б.Б.".to_string()], color: white, strikethrough: true },
+    ]);
+    assert_eq!(schema[1], vec![
+        table::Cell { x: 1, y: 1, colspan: 0, rowspan: 0, text: "".to_string(), lines: vec![], color: white, strikethrough: false },
+        table::Cell { x: 2, y: 1, colspan: 0, rowspan: 0, text: "Химия Сидоров В.В.".to_string(), lines: vec!["Химия Сидоров В.В.".to_string()], color: white, strikethrough: false },
+    ]);
+    assert_eq!(schema[2], vec![
+        table::Cell { x: 0, y: 2, colspan: 0, rowspan: 0, text: "пн 01.01".to_string(), lines: vec!["пн 01.01".to_string()], color: white, strikethrough: false },
+    ]);
+}
@@ -1,22 +1,26 @@
 //! # Two-step sheet parsing
 //! - `html`: converting HTML to a table
+//! - `xlsx`: converting XLSX worksheets to tables
 //! - `table`: mapping the table: connecting subjects
 //!   to groups/teachers and dates, constructing a `Page`
 
 pub mod html;
+pub mod xlsx;
 pub mod table;
 
 use log::{error, debug};
-use std::path::PathBuf;
-use crate::data::schedule;
-use crate::data::schedule::raw::Kind;
-use crate::lifetime;
+use std::path::{Path, PathBuf};
+use crate::data::schedule::{self, File};
+use crate::data::schedule::raw::{Kind, Limits};
 
 
+pub const XLSX_EXTENSION: &str = "xlsx";
+
 #[derive(thiserror::Error, Debug)]
 #[error("parsing error")]
 pub enum ParsingError {
     Html(html::ParsingError),
+    Xlsx(xlsx::ParsingError),
     Table(table::ParsingError),
 }
 impl From<html::ParsingError> for ParsingError {
@@ -24,18 +28,32 @@ impl From<html::ParsingError> for ParsingError {
         Self::Html(value)
     }
 }
+impl From<xlsx::ParsingError> for ParsingError {
+    fn from(value: xlsx::ParsingError) -> Self {
+        Self::Xlsx(value)
+    }
+}
 impl From<table::ParsingError> for ParsingError {
     fn from(value: table::ParsingError) -> Self {
         Self::Table(value)
     }
 }
 
+/// # Pages parsed from a single file
+/// An XLSX file may give several pages.
+pub struct Parsed {
+    pub path: PathBuf,
+    pub results: Vec<Result<schedule::Page, ParsingError>>,
+    pub took: std::time::Duration
+}
+
 
-pub async fn from_path(
-    path: &PathBuf,
+pub async fn from_file(
+    file: &File,
     kind: Kind,
 ) -> Result<schedule::Page, ParsingError> {
-    let html_processor = html::Parser::from_path(path).await;
+    let path = &file.path;
+    let html_processor = html::Parser::from_bytes(&file.bytes).await;
     if let Err(err) = html_processor {
         error!("error parsing {:?}: {:?}", path, err);
         return Err(err.into());
@@ -58,26 +76,80 @@ pub async fn from_path(
     Ok(mappings)
 }
 
-pub async fn from_paths(
-    paths: &[PathBuf],
+/// # Parse every visible worksheet of an XLSX file
+/// `limits` apply to the unpacked parts of the workbook.
+pub async fn from_xlsx_file(
+    file: &File,
     kind: Kind,
+    limits: &Limits
 ) -> Vec<Result<schedule::Page, ParsingError>> {
-    let mut handles = vec![];
+    let path = &file.path;
+    let xlsx_processor = xlsx::Parser::from_bytes(file.bytes.clone(), limits).await;
+    if let Err(err) = xlsx_processor {
+        error!("error parsing {:?}: {:?}", path, err);
+        return vec![Err(err.into())];
+    }
+    let xlsx_processor = xlsx_processor.unwrap();
+    let sheets = xlsx_processor.parse().await;
+    if let Err(err) = sheets {
+        error!("error parsing {:?}: {:?}", path, err);
+        return vec![Err(err.into())];
+    }
+    let sheets = sheets.unwrap();
+
     let mut page_results = vec![];
 
-    for path in paths {
-        let wrapped_path = unsafe {
-            lifetime::extend(lifetime::Wrap(path))
-        };
+    for sheet in sheets {
+        let table_processor = table::Parser::from_schema(sheet.schema, kind);
+        let mappings = table_processor.parse().await;
+        if let Err(err) = mappings {
+            error!("error parsing {:?} ({}): {:?}", path, sheet.name, err);
+            page_results.push(Err(err.into()));
+            continue;
+        }
+        debug!("{:?} ({}) parsed", path, sheet.name);
+        page_results.push(Ok(mappings.unwrap()));
+    }
+
+    page_results
+}
+
+fn is_xlsx(path: &Path) -> bool {
+    path.extension() == Some(std::ffi::OsStr::new(XLSX_EXTENSION))
+}
+
+/// # Parse every file
+pub async fn from_files(
+    files: &[File],
+    kind: Kind,
+    limits: &Limits
+) -> Vec<Parsed> {
+    let mut handles = vec![];
+    let mut file_results = vec![];
+
+    for file in files {
+        let file = file.clone();
+        let limits = limits.clone();
         let handle = tokio::spawn(async move {
-            from_path(&wrapped_path.0, kind).await
+            let started = std::time::Instant::now();
+            let results = if is_xlsx(&file.path) {
+                from_xlsx_file(&file, kind, &limits).await
+            } else {
+                vec![from_file(&file, kind).await]
+            };
+
+            Parsed {
+                path: file.path,
+                results,
+                took: started.elapsed()
+            }
         });
         handles.push(handle);
     }
 
     for handle in handles {
-        page_results.push(handle.await.unwrap());
+        file_results.push(handle.await.unwrap());
     }
 
-    page_results
-}
\ No newline at end of file
+    file_results
+}
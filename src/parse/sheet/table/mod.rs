@@ -1,4 +1,8 @@
-use chrono::TimeDelta;
+#[cfg(test)]
+mod tests;
+
+use chrono::{NaiveDate, TimeDelta};
+use log::warn;
 use crate::data::schedule::{
     self,
     raw::{
@@ -13,7 +17,7 @@ use crate::data::schedule::{
         }
     }
 };
-use crate::{parse, regexes};
+use crate::{options, parse, regexes};
 
 
 #[derive(thiserror::Error, Debug)]
@@ -50,41 +54,76 @@ impl Parser {
 
     pub fn date_ranges(&self) -> Option<Vec<table::Date>> {
         let row = self.date_row()?;
+        let today = options().settings().now().date();
         let mut opt_ranges: Vec<table::OptDate> = vec![];
         let mut ranges: Vec<table::Date> = vec![];
 
         // skip(1) because first cell is a formation identifier
         // that could be considered a subject if not skipped
-        for cell in row.iter().skip(1) {
+        let cells = row.iter().skip(1).collect::<Vec<&table::Cell>>();
+
+        // first and last date of each cell, `None` if it has none
+        let cell_partials = cells.iter().map(|cell| {
             let date_matches = regexes()
                 .date
                 .find_iter(&cell.text)
                 .collect::<Vec<regex::Match>>();
 
-            if !date_matches.is_empty() {
-                let start = parse::date::whole(
-                    date_matches.first().unwrap().as_str()
-                ).unwrap();
-                let end = parse::date::whole(
-                    date_matches.last().unwrap().as_str()
-                ).unwrap();
-
-                let opt_date = table::OptDate {
-                    raw: &cell.text,
-                    parsed: Some(start..=end),
-                    range: (cell.x())..(cell.x() + cell.width() - 1)
-                };
+            let start = parse::date::partial(date_matches.first()?.as_str());
+            let end = parse::date::partial(date_matches.last()?.as_str());
 
-                opt_ranges.push(opt_date);
-            } else {
-                let opt_date = table::OptDate {
-                    raw: &cell.text,
-                    parsed: None,
-                    range: (cell.x())..(cell.x() + cell.width() - 1)
-                };
+            let (start, end) = match (start, end) {
+                (Ok(start), Ok(end)) => (start, end),
+                (start, end) => {
+                    warn!("invalid dates in {:?}: {:?}, {:?}", cell.text, start.err(), end.err());
+                    return None
+                }
+            };
+
+            // a date that doesn't exist in any year
+            // is skipped instead of breaking the whole row
+            if let Err(err) = parse::date::infer(&[start, end], today) {
+                warn!("invalid dates in {:?}: {:?}", cell.text, err);
+                return None
+            }
 
-                opt_ranges.push(opt_date);
+            Some((start, end))
+        }).collect::<Vec<Option<(parse::date::Partial, parse::date::Partial)>>>();
+
+        // years are inferred for the whole row at once
+        // so that it doesn't break at New Year
+        let flat_partials = cell_partials
+            .iter()
+            .flatten()
+            .flat_map(|(start, end)| [*start, *end])
+            .collect::<Vec<parse::date::Partial>>();
+        let mut inferred = match parse::date::infer(&flat_partials, today) {
+            Ok(dates) => dates.into_iter(),
+            Err(err) => {
+                warn!("can't infer years of the date row, inferring each cell on its own: {:?}", err);
+                cell_partials
+                    .iter()
+                    .flatten()
+                    .flat_map(|(start, end)| parse::date::infer(&[*start, *end], today).unwrap_or_default())
+                    .collect::<Vec<NaiveDate>>()
+                    .into_iter()
             }
+        };
+
+        for (cell, partials) in cells.into_iter().zip(cell_partials) {
+            let parsed = partials.and_then(|_| {
+                let start = inferred.next()?;
+                let end = inferred.next()?;
+                Some(start..=end)
+            });
+
+            let opt_date = table::OptDate {
+                raw: &cell.text,
+                parsed,
+                range: (cell.x())..(cell.x() + cell.width() - 1)
+            };
+
+            opt_ranges.push(opt_date);
         }
 
         // can't modify in-place
@@ -183,7 +222,7 @@ impl Parser {
     }
 
     pub async fn parse<'a>(&'a self) -> Result<schedule::Page, ParsingError> {
-        let Some(dates) = self.date_ranges() else {
+        let Some(dates) = self.date_ranges().filter(|dates| !dates.is_empty()) else {
             return Err(ParsingError::NoDatesRow)
         };
 
@@ -233,13 +272,19 @@ impl Parser {
                 }) else {
                     continue
                 };
+                let code = match self.kind {
+                    raw::Kind::Groups => parse::group::code(&valid_formation),
+                    raw::Kind::Teachers => None,
+                };
 
                 current_formation = Some(table::Formation {
                     range: first_cell.y_range(),
                     object: schedule::Formation {
                         raw: first_cell.text.clone(),
                         recovered: false,
+                        stale: false,
                         name: valid_formation,
+                        code,
                         days: vec![]
                     }
                 });
@@ -286,86 +331,41 @@ impl Parser {
                         continue;
                     }
 
-                    let subject = match self.kind {
-                        raw::Kind::Groups => parse::subject::groups(
-                            &text,
-                            num_counter,
-                            cell.color
-                        ),
-                        raw::Kind::Teachers => parse::subject::teachers(
-                            &text,
-                            num_counter,
-                            cell.color
-                        )
-                    };
-
-                    let existing_days = current_formation
-                        .as_mut()
-                        .unwrap()
-                        .object
-                        .days
-                        .iter_mut()
-                        .filter(|day| current_date.parsed.contains(&day.date))
-                        .collect::<Vec<&mut schedule::Day>>();
-
-                    if !existing_days.is_empty() {
-                        for day in existing_days {
-                            day.subjects.push(subject.clone());
+                    for mut subject in self.subjects_from_cell(cell, num_counter) {
+                        if subject.subgroup.is_none() {
+                            subject.set_subgroup(Self::grid_subgroup(
+                                cell,
+                                row,
+                                &y_hits,
+                                y,
+                                current_date
+                            ));
                         }
-                    } else {
-                        let mut days = Self::days_from_subject(
-                            &subject,
-                            &current_date
+
+                        Self::push_subject(
+                            &mut current_formation.as_mut().unwrap().object,
+                            subject,
+                            current_date
                         );
-                        current_formation
-                            .as_mut()
-                            .unwrap()
-                            .object
-                            .days
-                            .append(&mut days);
                     }
                 } else if !hits_for_this_pos.is_empty() {
                     for hit in hits_for_this_pos {
-                        let cell = &hit.by;
-                        let text = cell.text.replace("\n", " ");
-
-                        let subject = match self.kind {
-                            raw::Kind::Groups => parse::subject::groups(
-                                &text,
-                                num_counter,
-                                cell.color
-                            ),
-                            raw::Kind::Teachers => parse::subject::teachers(
-                                &text,
-                                num_counter,
-                                cell.color
-                            )
-                        };
-
-                        let existing_days = current_formation
-                            .as_mut()
-                            .unwrap()
-                            .object
-                            .days
-                            .iter_mut()
-                            .filter(|day| current_date.parsed.contains(&day.date))
-                            .collect::<Vec<&mut schedule::Day>>();
-
-                        if !existing_days.is_empty() {
-                            for day in existing_days {
-                                day.subjects.push(subject.clone());
+                        for mut subject in self.subjects_from_cell(hit.by, num_counter) {
+                            if subject.subgroup.is_none() {
+                                subject.set_subgroup(Self::grid_subgroup(
+                                    hit.by,
+                                    row,
+                                    &y_hits,
+                                    y,
+                                    current_date
+                                ));
                             }
-                        } else {
-                            let mut days = Self::days_from_subject(
-                                &subject,
-                                &current_date
+
+                            Self::push_subject(
+                                &mut current_formation.as_mut().unwrap().object,
+                                subject,
+                                current_date
                             );
-                            current_formation
-                                .as_mut()
-                                .unwrap()
-                                .object
-                                .days
-                                .append(&mut days);
                         }
                     }
                 }
@@ -419,6 +419,94 @@ impl Parser {
         None
     }
 
+    /// # Add a subject to every day of `dates`
+    /// Days the formation doesn't have yet are created.
+    fn push_subject(
+        formation: &mut schedule::Formation,
+        subject: schedule::Subject,
+        dates: &table::Date
+    ) {
+        let existing_days = formation.days
+            .iter_mut()
+            .filter(|day| dates.parsed.contains(&day.date))
+            .collect::<Vec<&mut schedule::Day>>();
+
+        if !existing_days.is_empty() {
+            for day in existing_days {
+                day.subjects.push(subject.clone());
+            }
+        } else {
+            let mut days = Self::days_from_subject(&subject, dates);
+            formation.days.append(&mut days);
+        }
+    }
+
+    /// # Every lesson written in a cell
+    /// A struck through cell cancels all of them.
+    fn subjects_from_cell(&self, cell: &table::Cell, num: u32) -> Vec<schedule::Subject> {
+        let text = cell.text.replace("\n", " ");
+        let mut subjects = parse::subject::from_lines(
+            &text,
+            &cell.lines,
+            num,
+            cell.color,
+            self.kind
+        );
+
+        if cell.strikethrough {
+            for subject in subjects.iter_mut() {
+                subject.status = schedule::Status::Cancelled;
+            }
+        }
+
+        subjects
+    }
+
+    /// # Subgroup of a cell in a horizontally split date column
+    /// If a row has several lessons under the same date,
+    /// each of them is for its own subgroup, numbered left to right.
+    /// Lessons spreading from the previous rows are counted too,
+    /// cells with just a cabinet are not.
+    fn grid_subgroup(
+        cell: &table::Cell,
+        row: &[table::Cell],
+        y_hits: &[table::RangeHit],
+        y: usize,
+        date: &table::Date
+    ) -> Option<u32> {
+        let spreading = y_hits
+            .iter()
+            .filter(|hit| hit.by.y_range().contains(&y))
+            .map(|hit| hit.by);
+
+        let mut lessons = row
+            .iter()
+            .chain(spreading)
+            .filter(|other| other.x == date.x() || date.x_range().contains(&other.x))
+            .filter(|other| {
+                let text = other.text.trim();
+                let is_just_a_cabinet = parse::cabinet::from_end(text)
+                    .is_some_and(|cab| cab.start() == 0);
+
+                !text.is_empty() &&
+                (text.len() != 1 || !regexes().digit.is_match(text)) &&
+                !is_just_a_cabinet
+            })
+            .collect::<Vec<&table::Cell>>();
+
+        lessons.sort_by_key(|other| other.x);
+        lessons.dedup_by_key(|other| other.x);
+
+        if lessons.len() < 2 {
+            return None
+        }
+
+        lessons
+            .iter()
+            .position(|other| other.x == cell.x)
+            .map(|idx| idx as u32 + 1)
+    }
+
     pub fn cell_for_x<Cell: XCord + XRange>(x: usize, row: &Vec<Cell>) -> Option<&Cell> {
         for cell in row {
             if cell.x() == x || cell.x_range().contains(&x) {
@@ -0,0 +1,77 @@
+use super::*;
+
+
+fn cell(x: usize, y: usize, colspan: usize, rowspan: usize, text: &str) -> table::Cell {
+    table::Cell {
+        x,
+        y,
+        colspan,
+        rowspan,
+        text: text.to_string(),
+        lines: vec![text.to_string()],
+        color: palette::Srgb::new(255.0, 255.0, 255.0),
+        strikethrough: false
+    }
+}
+
+#[tokio::test]
+async fn test_grid_subgroups() {
+    crate::init_test_globals().await;
+
+    // ```notrust
+    //        | пн 01.01                       |
+    // 4КРД36 | Математика    | Физика         |
+    //        |               | Химия          |
+    //        | Биология      | 101            |
+    // ```
+    let schema = vec![
+        vec![cell(0, 0, 0, 0, ""), cell(1, 0, 3, 0, "пн 01.01")],
+        vec![
+            cell(0, 1, 0, 3, "4КРД36"),
+            cell(1, 1, 0, 2, "Математика Иванова А.А."),
+            cell(2, 1, 0, 0, "Физика Петров Б.Б.")
+        ],
+        vec![cell(2, 2, 0, 0, "Химия Сидоров В.В.")],
+        vec![cell(1, 3, 0, 0, "Биология Смирнова Г.Г."), cell(2, 3, 0, 0, "101")],
+    ];
+    let parser = Parser::from_schema(schema, raw::Kind::Groups);
+    let page = parser.parse().await.unwrap();
+
+    let subjects = page.formations[0].days[0].subjects
+        .iter()
+        .map(|subject| (subject.name.as_str(), subject.num, subject.subgroup))
+        .collect::<Vec<(&str, u32, Option<u32>)>>();
+
+    // a lesson spreading from the previous row
+    // keeps its subgroup, a cabinet column doesn't split
+    assert_eq!(subjects, vec![
+        ("Математика", 1, Some(1)),
+        ("Физика", 1, Some(2)),
+        ("Математика", 2, Some(1)),
+        ("Химия", 2, Some(2)),
+        ("Биология", 3, None),
+        ("101", 3, None),
+    ]);
+}
+
+#[tokio::test]
+async fn test_invalid_date_in_row() {
+    crate::init_test_globals().await;
+
+    let schema = vec![vec![
+        cell(0, 0, 0, 0, ""),
+        cell(1, 0, 0, 0, "пт 01.03.24"),
+        cell(2, 0, 0, 0, "сб 29.02"),
+        cell(3, 0, 0, 0, "вс 03.03.24"),
+    ]];
+    let parser = Parser::from_schema(schema, raw::Kind::Groups);
+
+    // 29.02 can't follow 01.03.24, the other
+    // dates of the row are still kept
+    let dates = parser.date_ranges().unwrap()
+        .iter()
+        .map(|date| date.parsed.start().format("%d.%m.%Y").to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(dates.first().map(String::as_str), Some("01.03.2024"));
+    assert_eq!(dates.last().map(String::as_str), Some("03.03.2024"));
+}
@@ -0,0 +1,497 @@
+//! # XLSX export parsing
+//! An alternative to `html` that reads
+//! the `export?format=xlsx` file and produces
+//! the same table for `table::Parser`.
+
+#[cfg(test)]
+mod tests;
+
+use async_zip::tokio::read::seek::ZipFileReader;
+use bytes::Bytes;
+use tokio::io::AsyncReadExt;
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+use std::{collections::{HashMap, HashSet}, io::Cursor, path::PathBuf};
+use crate::data::schedule::raw::{error::LimitError, table, Limits};
+
+
+const RELATIONSHIPS_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const WORKBOOK: &str = "xl/workbook.xml";
+const WORKBOOK_RELS: &str = "xl/_rels/workbook.xml.rels";
+const SHARED_STRINGS: &str = "xl/sharedStrings.xml";
+const STYLES: &str = "xl/styles.xml";
+const PARTS_DIR: &str = "xl/";
+const HIDDEN: &str = "hidden";
+const SHARED_STRING_TYPE: &str = "s";
+const INLINE_STRING_TYPE: &str = "inlineStr";
+const MIN_ROW_HEIGHT: f32 = 3.0;
+/// # `XFD`, the last column Excel allows
+const MAX_COLUMNS: usize = 16384;
+const MAX_ROWS: usize = 1048576;
+/// # How many cells a sheet table may have,
+/// a single far away cell or merge would
+/// otherwise make it enormous
+const MAX_CELLS: usize = 200_000;
+const DEFAULT_CELL_COLOR: palette::Srgb = {
+    palette::Srgb::new(255.0, 255.0, 255.0)
+};
+
+
+#[derive(thiserror::Error, Debug)]
+#[error("xlsx parsing error")]
+pub enum ParsingError {
+    LoadIO(std::io::Error),
+    LoadZip(async_zip::error::ZipError),
+    LoadXml(roxmltree::Error),
+    MissingPart(String),
+    InvalidReference(String),
+    TooManyCells { count: usize, max: usize },
+    Limit(LimitError),
+}
+impl From<std::io::Error> for ParsingError {
+    fn from(value: std::io::Error) -> Self {
+        Self::LoadIO(value)
+    }
+}
+impl From<async_zip::error::ZipError> for ParsingError {
+    fn from(value: async_zip::error::ZipError) -> Self {
+        Self::LoadZip(value)
+    }
+}
+impl From<roxmltree::Error> for ParsingError {
+    fn from(value: roxmltree::Error) -> Self {
+        Self::LoadXml(value)
+    }
+}
+impl From<LimitError> for ParsingError {
+    fn from(value: LimitError) -> Self {
+        Self::Limit(value)
+    }
+}
+
+
+/// # Cell formatting we care about
+#[derive(Debug, Clone)]
+struct Style {
+    color: palette::Srgb,
+    strikethrough: bool
+}
+impl Default for Style {
+    fn default() -> Self {
+        Self { color: DEFAULT_CELL_COLOR, strikethrough: false }
+    }
+}
+
+/// # Rectangle of merged cells, inclusive
+#[derive(Debug, Clone)]
+struct Merge {
+    x1: usize,
+    y1: usize,
+    x2: usize,
+    y2: usize
+}
+impl Merge {
+    fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x1..=self.x2).contains(&x) && (self.y1..=self.y2).contains(&y)
+    }
+
+    fn is_origin(&self, x: usize, y: usize) -> bool {
+        self.x1 == x && self.y1 == y
+    }
+}
+
+/// # Single worksheet converted to a table
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub name: String,
+    pub schema: Vec<Vec<table::Cell>>
+}
+
+pub struct Parser {
+    /// # XML parts of the workbook by their path in the archive
+    pub parts: HashMap<String, String>
+}
+impl Parser {
+    /// # Read the XML parts of a workbook
+    /// Their unpacked sizes are checked against `limits`,
+    /// the same way as entries of a ZIP export.
+    pub async fn from_bytes(bytes: Bytes, limits: &Limits) -> Result<Self, ParsingError> {
+        let mut archive = ZipFileReader::with_tokio(Cursor::new(bytes)).await?;
+        let mut parts = HashMap::new();
+        let mut total: u64 = 0;
+
+        for index in 0..archive.file().entries().len() {
+            let entry = archive.file().entries().get(index).unwrap();
+            let Ok(filename) = entry.filename().as_str().map(|name| name.to_string()) else {
+                continue
+            };
+            let is_xml = filename.ends_with(".xml") || filename.ends_with(".rels");
+            if !filename.starts_with(PARTS_DIR) || !is_xml {
+                continue;
+            }
+
+            // the declared size may be a lie,
+            // so it's also checked after reading
+            limits.check_size(&filename, entry.uncompressed_size(), total)?;
+            let budget = limits.entry_budget(total).map_or(u64::MAX, |budget| budget + 1);
+
+            let mut buf = vec![];
+            archive
+                .reader_without_entry(index)
+                .await?
+                .compat()
+                .take(budget)
+                .read_to_end(&mut buf)
+                .await?;
+
+            limits.check_size(&filename, buf.len() as u64, total)?;
+            total += buf.len() as u64;
+
+            let string = String::from_utf8(buf).map_err(
+                |error| std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+            )?;
+            parts.insert(filename, string);
+        }
+
+        Ok(Self { parts })
+    }
+
+    pub async fn from_path(path: &PathBuf, limits: &Limits) -> Result<Self, ParsingError> {
+        let bytes = tokio::fs::read(path).await?;
+        Self::from_bytes(bytes.into(), limits).await
+    }
+
+    fn part(&self, name: &str) -> Result<&str, ParsingError> {
+        self.parts
+            .get(name)
+            .map(|part| part.as_str())
+            .ok_or(ParsingError::MissingPart(name.to_string()))
+    }
+
+    /// # Names and part paths of visible worksheets,
+    /// in the workbook order
+    fn sheet_parts(&self) -> Result<Vec<(String, String)>, ParsingError> {
+        let rels = roxmltree::Document::parse(self.part(WORKBOOK_RELS)?)?;
+        let targets = rels.descendants()
+            .filter(|node| node.has_tag_name("Relationship"))
+            .filter_map(|node| Some((node.attribute("Id")?, node.attribute("Target")?)))
+            .map(|(id, target)| {
+                let path = if let Some(absolute) = target.strip_prefix('/') {
+                    absolute.to_string()
+                } else {
+                    format!("{}{}", PARTS_DIR, target)
+                };
+                (id.to_string(), path)
+            })
+            .collect::<HashMap<String, String>>();
+
+        let workbook = roxmltree::Document::parse(self.part(WORKBOOK)?)?;
+        let sheets = workbook.descendants()
+            .filter(|node| node.has_tag_name("sheet"))
+            .filter(|node| node.attribute("state").is_none_or(|state| state != HIDDEN))
+            .filter_map(|node| {
+                let name = node.attribute("name")?.to_string();
+                let id = node.attribute((RELATIONSHIPS_NS, "id"))?;
+                let path = targets.get(id)?.clone();
+                Some((name, path))
+            })
+            .collect();
+
+        Ok(sheets)
+    }
+
+    /// # Strings referenced by cells of type `s`
+    fn shared_strings(&self) -> Result<Vec<String>, ParsingError> {
+        // a workbook without any text doesn't have this part
+        let Ok(part) = self.part(SHARED_STRINGS) else { return Ok(vec![]) };
+        let document = roxmltree::Document::parse(part)?;
+
+        let strings = document.root_element()
+            .children()
+            .filter(|node| node.has_tag_name("si"))
+            .map(|si| Self::rich_text(&si))
+            .collect();
+
+        Ok(strings)
+    }
+
+    /// # Concatenate all `<t>` inside a rich text node,
+    /// skipping phonetic hints
+    fn rich_text(node: &roxmltree::Node) -> String {
+        node.descendants()
+            .filter(|node| node.has_tag_name("t"))
+            .filter(|node| !node.ancestors().any(|anc| anc.has_tag_name("rPh")))
+            .filter_map(|node| node.text())
+            .collect()
+    }
+
+    /// # `<cellXfs>` entries, cells reference them by index
+    fn styles(&self) -> Result<Vec<Style>, ParsingError> {
+        let Ok(part) = self.part(STYLES) else { return Ok(vec![]) };
+        let document = roxmltree::Document::parse(part)?;
+
+        let fills = document.descendants()
+            .find(|node| node.has_tag_name("fills"))
+            .map(|fills| fills.children()
+                .filter(|node| node.has_tag_name("fill"))
+                .map(|fill| fill.descendants()
+                    .find(|node| node.has_tag_name("fgColor"))
+                    .and_then(|color| color.attribute("rgb"))
+                    .and_then(Self::argb_to_color)
+                )
+                .collect::<Vec<Option<palette::Srgb>>>()
+            )
+            .unwrap_or_default();
+
+        // `<strike/>` or `<strike val="1"/>`
+        let strikes = document.descendants()
+            .find(|node| node.has_tag_name("fonts"))
+            .map(|fonts| fonts.children()
+                .filter(|node| node.has_tag_name("font"))
+                .map(|font| font.children()
+                    .find(|node| node.has_tag_name("strike"))
+                    .is_some_and(|strike| !matches!(strike.attribute("val"), Some("0" | "false")))
+                )
+                .collect::<Vec<bool>>()
+            )
+            .unwrap_or_default();
+
+        let styles = document.descendants()
+            .find(|node| node.has_tag_name("cellXfs"))
+            .map(|xfs| xfs.children()
+                .filter(|node| node.has_tag_name("xf"))
+                .map(|xf| {
+                    let color = xf.attribute("fillId")
+                        .and_then(|id| id.parse::<usize>().ok())
+                        .and_then(|id| fills.get(id).cloned().flatten())
+                        .unwrap_or(DEFAULT_CELL_COLOR);
+                    let strikethrough = xf.attribute("fontId")
+                        .and_then(|id| id.parse::<usize>().ok())
+                        .and_then(|id| strikes.get(id).copied())
+                        .unwrap_or(false);
+                    Style { color, strikethrough }
+                })
+                .collect::<Vec<Style>>()
+            )
+            .unwrap_or_default();
+
+        Ok(styles)
+    }
+
+    /// # `"FFFCE5CD"` -> `#fce5cd`
+    fn argb_to_color(argb: &str) -> Option<palette::Srgb> {
+        let rgb = argb.get(argb.len().checked_sub(6)?..)?;
+        let red = u8::from_str_radix(&rgb[0..2], 16).ok()?;
+        let green = u8::from_str_radix(&rgb[2..4], 16).ok()?;
+        let blue = u8::from_str_radix(&rgb[4..6], 16).ok()?;
+
+        Some(palette::Srgb::new(red as f32, green as f32, blue as f32))
+    }
+
+    /// # `"AB12"` -> `(27, 11)`
+    /// References past `XFD1048576` are invalid.
+    pub fn cell_reference(reference: &str) -> Option<(usize, usize)> {
+        let digits_start = reference.find(|c: char| c.is_ascii_digit())?;
+        let (letters, digits) = reference.split_at(digits_start);
+        if letters.is_empty() {
+            return None;
+        }
+
+        let mut x: usize = 0;
+        for letter in letters.chars() {
+            if !letter.is_ascii_uppercase() {
+                return None;
+            }
+            x = x.checked_mul(26)?.checked_add(letter as usize - 'A' as usize + 1)?;
+        }
+        let y = digits.parse::<usize>().ok()?;
+        if !(1..=MAX_ROWS).contains(&y) || x > MAX_COLUMNS {
+            return None;
+        }
+
+        Some((x - 1, y - 1))
+    }
+
+    fn merge(reference: &str) -> Option<Merge> {
+        let (start, end) = reference.split_once(':')?;
+        let (x1, y1) = Self::cell_reference(start)?;
+        let (x2, y2) = Self::cell_reference(end)?;
+
+        Some(Merge {
+            x1: x1.min(x2),
+            y1: y1.min(y2),
+            x2: x1.max(x2),
+            y2: y1.max(y2)
+        })
+    }
+
+    fn schema(
+        part: &str,
+        shared_strings: &[String],
+        styles: &[Style]
+    ) -> Result<Vec<Vec<table::Cell>>, ParsingError> {
+        let document = roxmltree::Document::parse(part)?;
+
+        let mut values: HashMap<(usize, usize), (String, Style)> = HashMap::new();
+        // only needed for merges starting on a skipped row
+        let mut skipped_values: HashMap<(usize, usize), (String, Style)> = HashMap::new();
+        let mut skipped_rows = HashSet::new();
+        let mut max_x = 0;
+        let mut max_y = 0;
+
+        for row in document.descendants().filter(|node| node.has_tag_name("row")) {
+            let is_hidden = row.attribute(HIDDEN).is_some_and(|hidden| hidden == "1");
+            let is_too_low = row.attribute("ht")
+                .and_then(|height| height.parse::<f32>().ok())
+                .is_some_and(|height| height < MIN_ROW_HEIGHT);
+            let row_y = row.attribute("r")
+                .and_then(|r| r.parse::<usize>().ok())
+                .map(|r| r.saturating_sub(1));
+
+            let is_skipped = is_hidden || is_too_low;
+            if is_skipped {
+                if let Some(y) = row_y {
+                    skipped_rows.insert(y);
+                }
+            }
+
+            for cell in row.children().filter(|node| node.has_tag_name("c")) {
+                let reference = cell.attribute("r").unwrap_or_default();
+                let Some((x, y)) = Self::cell_reference(reference) else {
+                    return Err(ParsingError::InvalidReference(reference.to_string()))
+                };
+
+                let value = cell.children()
+                    .find(|node| node.has_tag_name("v"))
+                    .and_then(|node| node.text())
+                    .unwrap_or_default();
+                let text = match cell.attribute("t") {
+                    Some(SHARED_STRING_TYPE) => value
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|idx| shared_strings.get(idx))
+                        .cloned()
+                        .unwrap_or_default(),
+                    Some(INLINE_STRING_TYPE) => cell.children()
+                        .find(|node| node.has_tag_name("is"))
+                        .map(|is| Self::rich_text(&is))
+                        .unwrap_or_default(),
+                    _ => value.to_string()
+                };
+                let style = cell.attribute("s")
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .and_then(|s| styles.get(s))
+                    .cloned()
+                    .unwrap_or_default();
+
+                if is_skipped {
+                    skipped_values.insert((x, y), (text.trim().to_string(), style));
+                    continue;
+                }
+
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+                values.insert((x, y), (text.trim().to_string(), style));
+            }
+        }
+
+        let merges = document.descendants()
+            .filter(|node| node.has_tag_name("mergeCell"))
+            .filter_map(|node| node.attribute("ref"))
+            .filter_map(Self::merge)
+            // a merge starting on a skipped row starts on
+            // its first visible one, taking the value along
+            .filter_map(|mut merge| {
+                let first_visible = (merge.y1..=merge.y2).find(|y| !skipped_rows.contains(y))?;
+
+                if first_visible != merge.y1 {
+                    if let Some(value) = skipped_values.remove(&(merge.x1, merge.y1)) {
+                        values.insert((merge.x1, first_visible), value);
+                    }
+                    merge.y1 = first_visible;
+                }
+
+                Some(merge)
+            })
+            .collect::<Vec<Merge>>();
+
+        for merge in merges.iter() {
+            max_x = max_x.max(merge.x2);
+            max_y = max_y.max(merge.y2);
+        }
+
+        if values.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // skipped rows don't take a `y`, the same way
+        // as they are absent from the HTML table
+        let visible_rows = (0..=max_y)
+            .filter(|y| !skipped_rows.contains(y))
+            .collect::<Vec<usize>>();
+
+        let count = visible_rows.len().saturating_mul(max_x + 1);
+        if count > MAX_CELLS {
+            return Err(ParsingError::TooManyCells { count, max: MAX_CELLS })
+        }
+
+        let visible_in = |merge: &Merge| visible_rows
+            .iter()
+            .filter(|y| (merge.y1..=merge.y2).contains(*y))
+            .count();
+
+        let mut schema = vec![];
+
+        for (table_y, y) in visible_rows.iter().copied().enumerate() {
+            let mut cells = vec![];
+
+            for x in 0..=max_x {
+                let merge = merges.iter().find(|merge| merge.contains(x, y));
+                if merge.is_some_and(|merge| !merge.is_origin(x, y)) {
+                    continue;
+                }
+
+                let (colspan, rowspan) = merge
+                    .map(|merge| (merge.x2 - merge.x1 + 1, visible_in(merge)))
+                    .unwrap_or((0, 0));
+                let (text, style) = values
+                    .remove(&(x, y))
+                    .unwrap_or_default();
+                let lines = text
+                    .lines()
+                    .map(|line| line.trim().to_string())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<String>>();
+
+                cells.push(table::Cell {
+                    x,
+                    y: table_y,
+                    colspan,
+                    rowspan,
+                    text,
+                    lines,
+                    color: style.color,
+                    strikethrough: style.strikethrough
+                });
+            }
+
+            if !cells.is_empty() {
+                schema.push(cells);
+            }
+        }
+
+        Ok(schema)
+    }
+
+    pub async fn parse(&self) -> Result<Vec<Sheet>, ParsingError> {
+        let shared_strings = self.shared_strings()?;
+        let styles = self.styles()?;
+        let mut sheets = vec![];
+
+        for (name, path) in self.sheet_parts()? {
+            let schema = Self::schema(self.part(&path)?, &shared_strings, &styles)?;
+            sheets.push(Sheet { name, schema });
+        }
+
+        Ok(sheets)
+    }
+}
@@ -0,0 +1,202 @@
+use async_zip::{base::write::ZipFileWriter, Compression, ZipEntryBuilder};
+use crate::data::schedule::raw::error::LimitError;
+use super::*;
+
+
+const WORKBOOK_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+    <sheets>
+        <sheet name="Week" sheetId="1" r:id="rId1"/>
+        <sheet name="Hidden" sheetId="2" state="hidden" r:id="rId2"/>
+    </sheets>
+</workbook>"#;
+
+const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="worksheet" Target="worksheets/sheet1.xml"/>
+    <Relationship Id="rId2" Type="worksheet" Target="/xl/worksheets/sheet2.xml"/>
+</Relationships>"#;
+
+const SHARED_STRINGS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <si><t>4КРД36</t></si>
+    <si><r><t>пн </t></r><r><t>01.01</t></r></si>
+    <si><t>Математика Иванова А.А.</t></si>
+</sst>"#;
+
+const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <fills>
+        <fill><patternFill patternType="none"/></fill>
+        <fill><patternFill patternType="gray125"/></fill>
+        <fill><patternFill patternType="solid"><fgColor rgb="FFFCE5CD"/></patternFill></fill>
+    </fills>
+    <cellXfs>
+        <xf fillId="0"/>
+        <xf fillId="2"/>
+    </cellXfs>
+</styleSheet>"#;
+
+const SHEET_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <sheetData>
+        <row r="1"><c r="B1" t="s"><v>1</v></c></row>
+        <row r="2" ht="2" customHeight="1"><c r="A2"><v>0</v></c></row>
+        <row r="3"><c r="A3" t="s"><v>0</v></c><c r="B3" s="1" t="s"><v>2</v></c><c r="C3"><v>1</v></c></row>
+    </sheetData>
+    <mergeCells>
+        <mergeCell ref="B1:C1"/>
+        <mergeCell ref="A3:A4"/>
+    </mergeCells>
+</worksheet>"#;
+
+
+async fn workbook() -> Bytes {
+    let mut writer = ZipFileWriter::new(vec![]);
+    let parts = [
+        (WORKBOOK, WORKBOOK_XML),
+        (WORKBOOK_RELS, RELS_XML),
+        (SHARED_STRINGS, SHARED_STRINGS_XML),
+        (STYLES, STYLES_XML),
+        ("xl/worksheets/sheet1.xml", SHEET_XML),
+        ("xl/worksheets/sheet2.xml", SHEET_XML),
+    ];
+
+    for (name, xml) in parts {
+        let entry = ZipEntryBuilder::new(name.into(), Compression::Deflate);
+        writer.write_entry_whole(entry, xml.as_bytes()).await.unwrap();
+    }
+
+    writer.close().await.unwrap().into()
+}
+
+#[test]
+fn test_cell_reference() {
+    assert_eq!(Parser::cell_reference("A1"), Some((0, 0)));
+    assert_eq!(Parser::cell_reference("Z3"), Some((25, 2)));
+    assert_eq!(Parser::cell_reference("AB12"), Some((27, 11)));
+    assert_eq!(Parser::cell_reference("A0"), None);
+    assert_eq!(Parser::cell_reference("12"), None);
+    assert_eq!(Parser::cell_reference("XFD1048576"), Some((16383, 1048575)));
+    assert_eq!(Parser::cell_reference("XFE1"), None);
+    assert_eq!(Parser::cell_reference("A1048577"), None);
+    assert_eq!(Parser::cell_reference("ZZZZZZZZZZZZZZZZZZZZ1"), None);
+}
+
+#[test]
+fn test_hidden_row() {
+    let sheet = r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <sheetData>
+        <row r="1"><c r="A1" t="inlineStr"><is><t>4КРД36</t></is></c><c r="B1" t="inlineStr"><is><t>Математика</t></is></c></row>
+        <row r="2" hidden="1"><c r="B2" t="inlineStr"><is><t>Черновик</t></is></c></row>
+        <row r="3"><c r="B3" t="inlineStr"><is><t>Физика</t></is></c></row>
+    </sheetData>
+    <mergeCells>
+        <mergeCell ref="A1:A3"/>
+    </mergeCells>
+</worksheet>"#;
+
+    let schema = Parser::schema(sheet, &[], &[]).unwrap();
+
+    // the hidden row doesn't take a `y`,
+    // so the formation only spans lesson rows
+    assert_eq!(schema.len(), 2);
+    assert_eq!(schema[0][0].text, "4КРД36");
+    assert_eq!((schema[0][0].y, schema[0][0].rowspan), (0, 2));
+    assert_eq!(schema[1].iter().map(|cell| (cell.x, cell.y)).collect::<Vec<_>>(), vec![(1, 1)]);
+    assert_eq!(schema[1][0].text, "Физика");
+}
+
+#[test]
+fn test_merge_from_hidden_row() {
+    let sheet = r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <sheetData>
+        <row r="1" hidden="1"><c r="A1" t="inlineStr"><is><t>4КРД36</t></is></c><c r="B1" t="inlineStr"><is><t>Черновик</t></is></c></row>
+        <row r="2"><c r="B2" t="inlineStr"><is><t>Математика</t></is></c></row>
+        <row r="3"><c r="B3" t="inlineStr"><is><t>Физика</t></is></c></row>
+    </sheetData>
+    <mergeCells>
+        <mergeCell ref="A1:A3"/>
+    </mergeCells>
+</worksheet>"#;
+
+    let schema = Parser::schema(sheet, &[], &[]).unwrap();
+
+    // the value moves to the first visible row of the merge,
+    // other cells of the hidden row are still dropped
+    assert_eq!(schema.len(), 2);
+    assert_eq!(schema[0][0].text, "4КРД36");
+    assert_eq!((schema[0][0].x, schema[0][0].y, schema[0][0].rowspan), (0, 0, 2));
+    assert_eq!(schema[0][1].text, "Математика");
+    assert_eq!(schema[1].iter().map(|cell| (cell.x, cell.y)).collect::<Vec<_>>(), vec![(1, 1)]);
+}
+
+#[test]
+fn test_too_many_cells() {
+    let sheet = r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <sheetData>
+        <row r="1"><c r="A1" t="inlineStr"><is><t>4КРД36</t></is></c></row>
+    </sheetData>
+    <mergeCells>
+        <mergeCell ref="A1:XFD1048576"/>
+    </mergeCells>
+</worksheet>"#;
+
+    assert!(matches!(
+        Parser::schema(sheet, &[], &[]),
+        Err(ParsingError::TooManyCells { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_parse() {
+    let parser = Parser::from_bytes(workbook().await, &Limits::default()).await.unwrap();
+    let sheets = parser.parse().await.unwrap();
+
+    assert_eq!(sheets.len(), 1);
+    assert_eq!(sheets[0].name, "Week");
+
+    let schema = &sheets[0].schema;
+    let white = palette::Srgb::new(255.0, 255.0, 255.0);
+    let peach = palette::Srgb::new(252.0, 229.0, 205.0);
+
+    // the second row is too low and is skipped
+    // without taking a `y`, the fourth one is fully
+    // covered by A3:A4 but still has cells in other columns
+    assert_eq!(schema.len(), 3);
+    assert_eq!(schema[0], vec![
+        table::Cell { x: 0, y: 0, colspan: 0, rowspan: 0, text: "".to_string(), lines: vec![], color: white, strikethrough: false },
+        table::Cell { x: 1, y: 0, colspan: 2, rowspan: 1, text: "пн 01.01".to_string(), lines: vec!["пн 01.01".to_string()], color: white, strikethrough: false },
+    ]);
+    assert_eq!(schema[1], vec![
+        table::Cell { x: 0, y: 1, colspan: 1, rowspan: 2, text: "4КРД36".to_string(), lines: vec!["4КРД36".to_string()], color: white, strikethrough: false },
+        table::Cell { x: 1, y: 1, colspan: 0, rowspan: 0, text: "Математика Иванова А.А.".to_string(), lines: vec!["Математика Иванова А.А.".to_string()], color: peach, strikethrough: false },
+        table::Cell { x: 2, y: 1, colspan: 0, rowspan: 0, text: "1".to_string(), lines: vec!["1".to_string()], color: white, strikethrough: false },
+    ]);
+    assert_eq!(schema[2].iter().map(|cell| (cell.x, cell.y)).collect::<Vec<(usize, usize)>>(), vec![(1, 2), (2, 2)]);
+}
+
+#[tokio::test]
+async fn test_limits() {
+    let entry_limits = Limits {
+        max_entry_size: Some(SHEET_XML.len() as u64 - 1),
+        ..Default::default()
+    };
+    assert!(matches!(
+        Parser::from_bytes(workbook().await, &entry_limits).await,
+        Err(ParsingError::Limit(LimitError::EntrySize { .. }))
+    ));
+
+    // every part fits, but all of them together don't
+    let total_limits = Limits {
+        max_total_size: Some(SHEET_XML.len() as u64 * 2),
+        ..Default::default()
+    };
+    assert!(matches!(
+        Parser::from_bytes(workbook().await, &total_limits).await,
+        Err(ParsingError::Limit(LimitError::TotalSize { .. }))
+    ));
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{data::schedule::Status, regexes};
+
+
+/// # Detect a status marker and remove it
+/// Only the first marker counts, the rest of the text
+/// is left for the usual subject parsing.
+/// 
+/// ## Input/output examples
+/// - `"Химия (отмена) Иванова А.А."` -> `Cancelled`, `"Химия Иванова А.А."`
+/// - `"Замена: Физика"` -> `Replacement`, `"Физика"`
+/// - `"Химия перенос на 12.09"` -> `MovedTo`, `"Химия"`
+/// - `"Химия"` -> `Regular`, `"Химия"`
+pub fn from_text(string: &str) -> (Status, String) {
+    let Some(captures) = regexes().status.captures(string) else {
+        return (Status::Regular, string.to_string())
+    };
+
+    let status = if captures.name("cancelled").is_some() {
+        Status::Cancelled
+    } else if captures.name("replacement").is_some() {
+        Status::Replacement
+    } else {
+        match captures.name("direction").map(|dir| dir.as_str().to_lowercase()) {
+            Some(dir) if dir.starts_with('с') => Status::MovedFrom,
+            _ => Status::MovedTo
+        }
+    };
+
+    let marker = captures.get(0).unwrap();
+    let rest = format!("{} {}", &string[..marker.start()], &string[marker.end()..]);
+    let rest = rest
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    (status, rest)
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+
+#[tokio::test]
+async fn test_from_text() {
+    crate::init_test_globals().await;
+
+    let cases = [
+        ("Химия (отмена) Иванова А.А.", Status::Cancelled, "Химия Иванова А.А."),
+        ("ОТМЕНЕНО Химия", Status::Cancelled, "Химия"),
+        ("Замена: Физика Петров П.П.", Status::Replacement, "Физика Петров П.П."),
+        ("Химия перенос на 12.09", Status::MovedTo, "Химия"),
+        ("Перенос с 10.09 - Химия", Status::MovedFrom, "Химия"),
+        ("Химия перенесена", Status::MovedTo, "Химия"),
+        ("Химия Иванова А.А.", Status::Regular, "Химия Иванова А.А."),
+    ];
+
+    for (string, status, rest) in cases {
+        assert_eq!(from_text(string), (status, rest.to_string()), "{:?}", string);
+    }
+}
+
+#[tokio::test]
+async fn test_direction_is_a_word() {
+    crate::init_test_globals().await;
+
+    // "С" of "Сидоров" is not "перенос с"
+    assert_eq!(
+        from_text("Химия перенос Сидоров С.С."),
+        (Status::MovedTo, "Химия Сидоров С.С.".to_string())
+    );
+}
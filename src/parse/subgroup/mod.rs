@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests;
+
+use crate::regexes;
+
+
+/// # Detect a subgroup marker and remove it
+/// If markers of different subgroups are found,
+/// the cell is for several of them and is left as is.
+/// 
+/// ## Input/output examples
+/// - `"Химия 1 подгр. Иванова А.А."` -> `Some(1)`, `"Химия Иванова А.А."`
+/// - `"(п/г 2) Физика"` -> `Some(2)`, `"Физика"`
+/// - `"Химия"` -> `None`, `"Химия"`
+pub fn from_text(string: &str) -> (Option<u32>, String) {
+    let mut subgroup = None;
+    let mut markers = vec![];
+
+    for captures in regexes().subgroup.captures_iter(string) {
+        let Some(num) = captures.name("before")
+            .or_else(|| captures.name("after"))
+            .and_then(|num| num.as_str().parse::<u32>().ok())
+        else {
+            continue
+        };
+
+        if subgroup.is_some_and(|subgroup| subgroup != num) {
+            return (None, string.to_string())
+        }
+
+        subgroup = Some(num);
+        markers.push(captures.get(0).unwrap().range());
+    }
+
+    if markers.is_empty() {
+        return (None, string.to_string())
+    }
+
+    let mut rest = String::new();
+    let mut last = 0;
+    for marker in markers {
+        rest.push_str(&string[last..marker.start]);
+        rest.push(' ');
+        last = marker.end;
+    }
+    rest.push_str(&string[last..]);
+
+    let rest = rest
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    (subgroup, rest)
+}
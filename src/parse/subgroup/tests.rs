@@ -0,0 +1,27 @@
+use super::*;
+
+
+#[tokio::test]
+async fn test_from_text() {
+    crate::init_test_globals().await;
+
+    let cases = [
+        ("Химия 1 подгр. Иванова А.А.", Some(1), "Химия Иванова А.А."),
+        ("Химия (2-я подгруппа) Иванова А.А.", Some(2), "Химия Иванова А.А."),
+        ("(п/г 2) Физика", Some(2), "Физика"),
+        ("Физика 1пг", Some(1), "Физика"),
+        ("Химия Иванова А.А. 101", None, "Химия Иванова А.А. 101"),
+    ];
+
+    for (string, subgroup, rest) in cases {
+        assert_eq!(from_text(string), (subgroup, rest.to_string()), "{:?}", string);
+    }
+}
+
+#[tokio::test]
+async fn test_both_subgroups() {
+    crate::init_test_globals().await;
+
+    let string = "Ин. язык 1 подгр. Костина С.В. / 2 подгр. Хачатрян Н.В.";
+    assert_eq!(from_text(string), (None, string.to_string()));
+}
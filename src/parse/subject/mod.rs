@@ -15,10 +15,10 @@ pub fn format_from_color(color: palette::Srgb) -> schedule::raw::Format {
 
     let lab: palette::Lab = color.into_color();
     let fulltime_diff = lab.difference(
-        options().settings.parsing.fulltime_lab
+        options().settings().parsing.fulltime_lab
     );
     let remote_diff = lab.difference(
-        options().settings.parsing.remote_lab
+        options().settings().parsing.remote_lab
     );
 
     if fulltime_diff > 45.0 && remote_diff > 45.0 {
@@ -38,10 +38,96 @@ pub fn format_from_color(color: palette::Srgb) -> schedule::raw::Format {
     }
 }
 
+/// # Split cell lines into lessons
+/// 
+/// - in group schedules a lesson ends on a line with a teacher
+/// - in teacher schedules a lesson starts on a line with a group
+/// 
+/// A line with just a cabinet stays with
+/// the previous lesson, lines that can't be
+/// told apart stick to their neighbours
+pub fn split_lines(lines: &[String], kind: schedule::raw::Kind) -> Vec<String> {
+    let mut lessons: Vec<Vec<&str>> = vec![];
+    let mut current: Vec<&str> = vec![];
+
+    for line in lines.iter().map(|line| line.trim()) {
+        if line.is_empty() {
+            continue;
+        }
+
+        let is_just_a_cabinet = parse::cabinet::from_end(line)
+            .is_some_and(|cab| cab.start() == 0);
+
+        match kind {
+            schedule::raw::Kind::Groups => {
+                if is_just_a_cabinet && current.is_empty() {
+                    if let Some(last) = lessons.last_mut() {
+                        last.push(line);
+                        continue;
+                    }
+                }
+
+                current.push(line);
+
+                if parse::attender::teachers(line).is_some() {
+                    lessons.push(std::mem::take(&mut current));
+                }
+            },
+            schedule::raw::Kind::Teachers => {
+                let starts_with_group = parse::group::multi(line).is_some();
+
+                if starts_with_group && !current.is_empty() {
+                    lessons.push(std::mem::take(&mut current));
+                }
+
+                current.push(line);
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        lessons.push(current);
+    }
+
+    lessons
+        .into_iter()
+        .map(|lines| lines.join(" "))
+        .collect()
+}
+
+/// # Parse every lesson of a cell
+/// 
+/// Falls back to `text` as a single lesson
+/// if `lines` can't be split
+pub fn from_lines(
+    text: &str,
+    lines: &[String],
+    num: u32,
+    color: palette::Srgb,
+    kind: schedule::raw::Kind
+) -> Vec<schedule::Subject> {
+    let mut lessons = split_lines(lines, kind);
+
+    if lessons.len() < 2 {
+        lessons = vec![text.to_string()];
+    }
+
+    lessons
+        .iter()
+        .map(|lesson| match kind {
+            schedule::raw::Kind::Groups => groups(lesson, num, color),
+            schedule::raw::Kind::Teachers => teachers(lesson, num, color)
+        })
+        .collect()
+}
+
 pub fn groups(string: &str, num: u32, color: palette::Srgb) -> schedule::Subject {
     let raw = string.to_string();
     let recovered = false;
     let format = format_from_color(color);
+    let (status, string) = parse::status::from_text(string);
+    let (subgroup, string) = parse::subgroup::from_text(&string);
+    let string = string.as_str();
     let name;
     let attenders;
 
@@ -58,20 +144,30 @@ pub fn groups(string: &str, num: u32, color: palette::Srgb) -> schedule::Subject
         attenders = vec![];
     }
 
-    schedule::Subject {
+    let mut subject = schedule::Subject {
         raw,
         recovered,
         name,
         num,
+        subgroup: None,
         format,
-        attenders
-    }
+        status,
+        attenders,
+        start: None,
+        end: None
+    };
+    subject.set_subgroup(subgroup);
+
+    subject
 }
 
 pub fn teachers(string: &str, num: u32, color: palette::Srgb) -> schedule::Subject {
     let raw = string.to_string();
     let recovered = false;
     let format = format_from_color(color);
+    let (status, string) = parse::status::from_text(string);
+    let (subgroup, string) = parse::subgroup::from_text(&string);
+    let string = string.as_str();
     let mut name;
     let mut attenders;
 
@@ -86,6 +182,7 @@ pub fn teachers(string: &str, num: u32, color: palette::Srgb) -> schedule::Subje
                 recovered: false,
                 kind: att_kind.clone(),
                 name: att.1,
+                subgroup,
                 cabinet: att_cabinet.clone()
             })
             .collect::<Vec<schedule::Attender>>();
@@ -98,7 +195,9 @@ pub fn teachers(string: &str, num: u32, color: palette::Srgb) -> schedule::Subje
         let cab = schedule::Cabinet {
             recovered: false,
             primary: Some(cabinet_match.as_str().to_string()),
-            opposite: None
+            opposite: None,
+            primary_rooms: parse::cabinet::rooms(cabinet_match.as_str()),
+            opposite_rooms: vec![]
         };
         for att in attenders.iter_mut() {
             att.cabinet = cab.clone();
@@ -106,12 +205,19 @@ pub fn teachers(string: &str, num: u32, color: palette::Srgb) -> schedule::Subje
         name = (&name[..cabinet_match.start()]).trim().to_string();
     }
 
-    schedule::Subject {
+    let mut subject = schedule::Subject {
         raw,
         recovered,
         name,
         num,
+        subgroup: None,
         format,
-        attenders
-    }
+        status,
+        attenders,
+        start: None,
+        end: None
+    };
+    subject.set_subgroup(subgroup);
+
+    subject
 }
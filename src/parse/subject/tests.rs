@@ -1,23 +1,15 @@
 use colors_transform::Color;
-use crate::data;
 use crate::data::schedule;
 use super::*;
 
 
 #[tokio::test]
 async fn test_groups_1() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Учет страховых договоров Иванова А.А.";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -29,38 +21,38 @@ async fn test_groups_1() {
         recovered: false,
         name: "Учет страховых договоров".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Иванова А.А.".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Иванова А.А.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_2() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Ин. язык Костина С.В. / Хачатрян Н.В.";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -72,17 +64,22 @@ async fn test_groups_2() {
         recovered: false,
         name: "Ин. язык".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Костина С.В. / ".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Костина С.В.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -90,31 +87,29 @@ async fn test_groups_2() {
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Хачатрян Н.В.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_3() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Деловой англ. /Хачатрян Н.В.";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -126,38 +121,38 @@ async fn test_groups_3() {
         recovered: false,
         name: "Деловой англ.".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Хачатрян Н.В.".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Хачатрян Н.В.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_4() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Деловой англ. Коняева А.С.37а/Хачатрян Н.В.";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -169,17 +164,28 @@ async fn test_groups_4() {
         recovered: false,
         name: "Деловой англ.".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Коняева А.С.37а/".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Коняева А.С.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("37а".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "37а".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("37а".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -187,31 +193,29 @@ async fn test_groups_4() {
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Хачатрян Н.В.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_5() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Деловой англ. Коняева А.С. /Хачатрян Н.В. каб 2";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -223,17 +227,22 @@ async fn test_groups_5() {
         recovered: false,
         name: "Деловой англ.".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Коняева А.С. /".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Коняева А.С.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -241,31 +250,35 @@ async fn test_groups_5() {
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Хачатрян Н.В.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("каб 2".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "каб 2".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("2".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_6() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Деловой англ. Коняева А.С. 37а/Хачатрян Н.В. каб 2";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -277,17 +290,28 @@ async fn test_groups_6() {
         recovered: false,
         name: "Деловой англ.".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Коняева А.С. 37а/".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Коняева А.С.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("37а".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "37а".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("37а".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -295,31 +319,35 @@ async fn test_groups_6() {
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Хачатрян Н.В.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("каб 2".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "каб 2".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("2".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_7() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Экономика организации Вакансия 05";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -331,38 +359,38 @@ async fn test_groups_7() {
         recovered: false,
         name: "Экономика организации".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Вакансия 05".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Vacancy,
                 name: "Вакансия 05".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_groups_8() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "Выполнение дизайнерских проектов в материале Вакансия 02.3/Натус Н.И. каб. 4";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -374,17 +402,22 @@ async fn test_groups_8() {
         recovered: false,
         name: "Выполнение дизайнерских проектов в материале".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "Вакансия 02.3/".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Vacancy,
                 name: "Вакансия 02.3".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -392,31 +425,35 @@ async fn test_groups_8() {
                 recovered: false,
                 kind: schedule::attender::Kind::Teacher,
                 name: "Натус Н.И.".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("каб. 4".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "каб. 4".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("4".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(groups(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_teachers_1() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "3дд48 жив каб 17а";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -428,38 +465,44 @@ async fn test_teachers_1() {
         recovered: false,
         name: "жив".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "3дд48".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "3КДД48".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("каб 17а".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "каб 17а".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("17а".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(teachers(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_teachers_2() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "3рд33/4рд34/36 осн фил";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -471,17 +514,22 @@ async fn test_teachers_2() {
         recovered: false,
         name: "осн фил".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "3рд33".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "3КРД33".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -489,10 +537,13 @@ async fn test_teachers_2() {
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "4КРД34".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -500,31 +551,29 @@ async fn test_teachers_2() {
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "4КРД36".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(teachers(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_teachers_3() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "1 мп2\\4 лит-ра";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -536,17 +585,22 @@ async fn test_teachers_3() {
         recovered: false,
         name: "лит-ра".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "1 мп2".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "1КМП2".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             },
             schedule::Attender {
@@ -554,31 +608,29 @@ async fn test_teachers_3() {
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "1КМП4".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: None,
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(teachers(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_teachers_4() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "1ктд4   ОБЗР каб.40";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -590,38 +642,44 @@ async fn test_teachers_4() {
         recovered: false,
         name: "ОБЗР".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "1ктд4".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "1КТД4".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("каб.40".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "каб.40".to_string(),
+                        kind: schedule::RoomKind::Classroom,
+                        number: Some("40".to_string()),
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(teachers(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_teachers_5() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "2рд36 культ каб ?";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -633,38 +691,44 @@ async fn test_teachers_5() {
         recovered: false,
         name: "культ".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "2рд36".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "2КРД36".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("каб ?".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "каб ?".to_string(),
+                        kind: schedule::RoomKind::Unknown,
+                        number: None,
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(teachers(string, num, color), result);
 }
 
 #[tokio::test]
 async fn test_teachers_6() {
-    let data_path = [".", "data"].iter().collect();
-    let regex_own = data::regex::Container::default();
-    let data_own = data::Container::default_from_dir(data_path).await.unwrap();
-    unsafe {
-        crate::REGEX = &regex_own;
-        crate::DATA = &data_own;
-    }
+    crate::init_test_globals().await;
 
     let string = "1дд20 ПС актовый за";
     let num = 2;
-    let color = "#c6d9f0"
-        .parse::<colors_transform::Rgb>()
+    let color = colors_transform::Rgb::from_hex_str("#c6d9f0")
         .map(|color| palette::Srgb::new(
             color.get_red(),
             color.get_green(),
@@ -676,20 +740,65 @@ async fn test_teachers_6() {
         recovered: false,
         name: "ПС".to_string(),
         num: 2,
+        subgroup: None,
         format: schedule::raw::Format::Remote,
+        status: schedule::Status::Regular,
         attenders: vec![
             schedule::Attender {
                 raw: "1дд20".to_string(),
                 recovered: false,
                 kind: schedule::attender::Kind::Group,
                 name: "1КДД20".to_string(),
+                subgroup: None,
                 cabinet: schedule::Cabinet {
                     recovered: false,
                     primary: Some("актовый за".to_string()),
-                    opposite: None
+                    opposite: None,
+                    primary_rooms: vec![schedule::Room {
+                        raw: "актовый за".to_string(),
+                        kind: schedule::RoomKind::AssemblyHall,
+                        number: None,
+                        building: None,
+                        floor: None
+                    }],
+                    opposite_rooms: vec![]
                 }
             }
-        ]
+        ],
+        start: None,
+        end: None
     };
     assert_eq!(teachers(string, num, color), result);
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_split_groups_lines() {
+    crate::init_test_globals().await;
+
+    let lines = [
+        "Математика",
+        "Иванова А.А.",
+        "каб 101",
+        "Физика Петров Б.Б. 102",
+    ].map(|line| line.to_string());
+    let result = vec![
+        "Математика Иванова А.А. каб 101".to_string(),
+        "Физика Петров Б.Б. 102".to_string(),
+    ];
+    assert_eq!(split_lines(&lines, schedule::raw::Kind::Groups), result);
+}
+
+#[tokio::test]
+async fn test_split_teachers_lines() {
+    crate::init_test_globals().await;
+
+    let lines = [
+        "3дд48 жив",
+        "каб 17а",
+        "1ктд4 ОБЗР каб.40",
+    ].map(|line| line.to_string());
+    let result = vec![
+        "3дд48 жив каб 17а".to_string(),
+        "1ктд4 ОБЗР каб.40".to_string(),
+    ];
+    assert_eq!(split_lines(&lines, schedule::raw::Kind::Teachers), result);
+}
@@ -24,6 +24,16 @@ pub fn validate_tokens(tokens: &[&str]) -> String {
     output
 }
 
+/// # Canonical name from `aliases.json`,
+/// `name` itself if there is none
+fn resolve(matched: &str, name: String) -> String {
+    crate::options()
+        .aliases()
+        .teacher(matched, &name)
+        .map(|canonical| canonical.to_string())
+        .unwrap_or(name)
+}
+
 pub fn validate(string: &str) -> Option<String> {
     let matched = regexes()
         .teacher
@@ -39,7 +49,7 @@ pub fn validate(string: &str) -> Option<String> {
         return None;
     }
 
-    Some(validate_tokens(&tokens))
+    Some(resolve(matched, validate_tokens(&tokens)))
 }
 
 pub fn validate_all(string: &str) -> Vec<String> {
@@ -60,7 +70,7 @@ pub fn validate_all(string: &str) -> Vec<String> {
             continue;
         }
 
-        let valid = validate_tokens(&tokens);
+        let valid = resolve(m.as_str(), validate_tokens(&tokens));
         output.push(valid);
     }
 
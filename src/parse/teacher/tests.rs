@@ -1,9 +1,8 @@
 use super::*;
 
-#[test]
-fn test_validate() {
-    let regex_own = crate::data::regex::Container::default();
-    unsafe { crate::REGEX = &regex_own };
+#[tokio::test]
+async fn test_validate() {
+    crate::init_test_globals().await;
 
     assert_eq!(validate("Ебланов Х.Й."), Some("Ебланов Х.Й.".to_string()));
     assert_eq!(validate("Ебланов.Х.Й."), Some("Ебланов Х.Й.".to_string()));